@@ -37,6 +37,22 @@ const setNestedValue = (obj, path, value) => {
     schema[path[path.length - 1]] = value;
 };
 
+// helper to write a param's color while keeping other fields of the LinearColor (e.g. $type)
+const applyParamColor = (obj, param) => {
+    let current = obj;
+    for (const key of param.path) {
+        current = current?.[key];
+    }
+    setNestedValue(obj, param.path, { ...current, ...param.rgba });
+};
+
+// JSON pointer from a backend parameter summary -> path array used by setNestedValue
+const pointerToPath = (pointer) => pointer
+    .split('/')
+    .slice(1)
+    .map(token => token.replace(/~1/g, '/').replace(/~0/g, '~'))
+    .map(token => (/^\d+$/.test(token) ? Number(token) : token));
+
 const rgbaToDisplayHex = (r, g, b) => {
     const maxVal = Math.max(r, g, b, 1.0);
     const normR = r / maxVal;
@@ -112,6 +128,48 @@ function hslToRgb(h, s, l) {
     return [r, g, b];
 }
 
+// Remediation hints for the error codes returned by Tauri commands
+const ERROR_HINTS = {
+    tool_not_found: 'Build UAssetCLI or select it under Settings > Conversion Tool.',
+    tool_failed: 'Use Check under Settings > Conversion Tool to see whether UAssetCLI runs.',
+    usmap_missing: 'The .usmap file was moved or deleted. Select it again in Settings.',
+    usmap_invalid: 'Regenerate the .usmap for the current game version and select it in Settings.',
+    asset_parse_failed: 'The asset may not match the selected .usmap or Engine Version.',
+    io_error: 'Check that the file exists and is not open in another program.',
+    cache_corrupt: 'Use Repair under Settings > Conversion Cache, or clear the cache.',
+    invalid_json: 'Convert the asset again; the JSON file is not valid.',
+    invalid_setting: 'Pick one of the values offered in Settings.',
+    invalid_bundle: 'Export the cache bundle again; this file is not one or was cut off.',
+    busy: 'Wait for the running conversion to finish or cancel it.',
+};
+
+// Settings a root folder's .rvfxe.json can override, as named in the Settings dialog
+const PROJECT_SETTING_LABELS = {
+    usmapPath: '.usmap File Path',
+    activeMapping: 'Active Mapping',
+    engineVersion: 'Engine Version',
+    filterProfiles: 'Filter Profiles',
+    uassetCliPath: 'Conversion Tool',
+    conversionConcurrency: 'Conversion Processes',
+    verifyRoundTrip: 'Verify Saved Assets',
+    cacheLocation: 'Cache Location',
+    maxCacheBytes: 'Cache Size Limit',
+    autoClearCache: 'Auto Clear Cache on Exit',
+    showDetailedErrors: 'Detailed Errors',
+};
+
+// A library mapping or a .usmap file is needed before anything can be converted
+const hasMapping = (settings) => Boolean(settings.activeMapping || settings.usmapPath);
+
+// Formats a backend error, or anything else that was thrown, for display
+const describeError = (err, { details = false } = {}) => {
+    if (!err || typeof err !== 'object' || !err.code) return `${err?.message || err}`;
+    const hint = ERROR_HINTS[err.code];
+    let text = hint ? `${err.message} - ${hint}` : err.message;
+    if (details && err.tool_output) text += `\n${err.tool_output}`;
+    return text;
+};
+
 const StyledPanel = ({ title, children, className, ...props }) => {
     return (
         <div className={`relative group ${className}`} style={{ backgroundColor: 'var(--bg-3)' }} {...props}>
@@ -253,6 +311,75 @@ const KeywordListEditor = ({ title, keywords, onChange }) => {
     );
 };
 
+// Typed filter rules, matched in Rust together with the keywords. Text fields save on blur
+const FilterRulesEditor = ({ rules, onChange }) => {
+    const update = (index, changes) => onChange(rules.map((rule, i) => i === index ? { ...rule, ...changes } : rule));
+    const remove = (index) => onChange(rules.filter((_, i) => i !== index));
+    const add = () => onChange([...rules, { kind: 'glob', pattern: '', action: 'deny', priority: 1, case_sensitive: false }]);
+    const fieldStyle = { backgroundColor: 'var(--bg-2)', color: 'var(--text-1)' };
+
+    return (
+        <div className="mb-4">
+            <label className="block text-xs font-bold uppercase mb-1 opacity-70" style={{ color: 'var(--text-2)' }}>
+                Rules
+            </label>
+            <div className="space-y-1 mb-2 max-h-48 overflow-y-auto p-2 border rounded" style={{ backgroundColor: 'var(--bg-1)', borderColor: 'var(--bg-2)' }}>
+                {rules.length === 0 && <div className="text-xs opacity-50 px-1 italic">No rules</div>}
+                {rules.map((rule, idx) => (
+                    <div key={idx} className="flex items-center gap-1 text-xs">
+                        <select value={rule.action} onChange={(e) => update(idx, { action: e.target.value })} className="px-1 py-1 rounded-none" style={fieldStyle}>
+                            <option value="allow">Allow</option>
+                            <option value="deny">Deny</option>
+                        </select>
+                        <select value={rule.kind} onChange={(e) => update(idx, { kind: e.target.value })} className="px-1 py-1 rounded-none" style={fieldStyle}>
+                            <option value="substring">Contains</option>
+                            <option value="glob">Glob</option>
+                            <option value="regex">Regex</option>
+                            <option value="exact">Exact</option>
+                        </select>
+                        <input
+                            key={`pattern-${idx}-${rule.pattern}`}
+                            type="text"
+                            defaultValue={rule.pattern}
+                            onBlur={(e) => e.target.value !== rule.pattern && update(idx, { pattern: e.target.value })}
+                            onKeyDown={(e) => e.key === 'Enter' && e.target.blur()}
+                            placeholder="Pattern"
+                            className="flex-1 min-w-0 px-2 py-1 rounded-none focus:outline-none font-mono"
+                            style={fieldStyle}
+                        />
+                        <input
+                            key={`priority-${idx}-${rule.priority}`}
+                            type="number"
+                            defaultValue={rule.priority}
+                            onBlur={(e) => {
+                                const priority = parseInt(e.target.value, 10) || 0;
+                                if (priority !== rule.priority) update(idx, { priority });
+                            }}
+                            title="Priority: higher rules decide first, keywords are 0"
+                            className="w-12 px-1 py-1 rounded-none focus:outline-none"
+                            style={fieldStyle}
+                        />
+                        <label className="flex items-center gap-1 opacity-70" title="Case sensitive" style={{ color: 'var(--text-2)' }}>
+                            <input type="checkbox" checked={!!rule.case_sensitive} onChange={(e) => update(idx, { case_sensitive: e.target.checked })} />
+                            Aa
+                        </label>
+                        <button onClick={() => remove(idx)} className="text-red-500 hover:text-red-400 font-bold ml-1">
+                            ×
+                        </button>
+                    </div>
+                ))}
+            </div>
+            <button
+                onClick={add}
+                className="px-2 py-1 text-xs font-bold rounded-none"
+                style={{ backgroundColor: 'var(--accent-main)', color: 'var(--bg-4)' }}
+            >
+                + Rule
+            </button>
+        </div>
+    );
+};
+
 
 // MAIN APP COMPONENT
 function App() {
@@ -270,6 +397,19 @@ function App() {
         setDebugLog(prev => [...prev, `[${new Date().toLocaleTimeString()}] ${message}`]);
     }, []);
 
+    // Stops the running batch; the pending batch invoke resolves with the partial result
+    const handleCancelConversion = async () => {
+        setIsCancelling(true);
+        try {
+            const result = await invoke('cancel_conversion');
+            addDebugLog(`Cancelled conversion: ${result.succeeded}/${result.total} finished`);
+        } catch (err) {
+            addDebugLog(`Cancel failed: ${describeError(err)}`);
+        } finally {
+            setIsCancelling(false);
+        }
+    };
+
     const [shiftKeyPressed, setShiftKeyPressed] = useState(false);
     const [ctrlKeyPressed, setCtrlKeyPressed] = useState(false);
     const [altKeyPressed, setAltKeyPressed] = useState(false);
@@ -307,6 +447,23 @@ function App() {
         });
     }, [useFiveColors]);
 
+    // Settings files changed on disk are reloaded by the backend
+    useEffect(() => {
+        const unlistenChanged = listen('settings-changed', (event) => {
+            applyLayeredSettings(event.payload);
+            addDebugLog('Settings changed on disk and were reloaded');
+        });
+        const unlistenFailed = listen('settings-reload-failed', (event) => {
+            addDebugLog(`Settings changed on disk but were not reloaded: ${describeError(event.payload)}`);
+            alert(`Settings changed on disk but could not be reloaded: ${describeError(event.payload)}`);
+        });
+
+        return () => {
+            unlistenChanged.then(unlisten => unlisten());
+            unlistenFailed.then(unlisten => unlisten());
+        };
+    }, []);
+
     // Listen for conversion progress events
     useEffect(() => {
         const unlistenPromise = listen('conversion-progress', (event) => {
@@ -349,17 +506,28 @@ function App() {
     const [folders, setFolders] = useState([]);
     const [selectedFolders, setSelectedFolders] = useState(new Set());
     const [filterDictionary, setFilterDictionary] = useState(defaultFilterDictionary);
+    const [filterProfiles, setFilterProfiles] = useState({ active: 'Default', profiles: { Default: defaultFilterDictionary } });
+    const [profileNameDraft, setProfileNameDraft] = useState('');
+    const [settingLayers, setSettingLayers] = useState({ projectFile: null, sources: {} });
+    const [filterTestNames, setFilterTestNames] = useState('');
+    const [filterTestResults, setFilterTestResults] = useState(null);
     const [sortConfig, setSortConfig] = useState({ key: null, direction: 'none' });
 
     const [sessionName, setSessionName] = useState('YourProjectName');
 
     // === UASSET INTEGRATION STATE ===
-    const [settings, setSettings] = useState({ usmapPath: null, showDetailedErrors: true, autoClearCache: false });
+    const [settings, setSettings] = useState({ usmapPath: null, activeMapping: null, showDetailedErrors: true, autoClearCache: false, uassetCliPath: null, conversionConcurrency: 1, verifyRoundTrip: false, engineVersion: 'VER_UE5_3', maxCacheBytes: null, cacheLocation: null });
+    const [toolCheck, setToolCheck] = useState(null);
+    const [engineVersions, setEngineVersions] = useState([]);
     const [isConverting, setIsConverting] = useState(false);
+    const [isCancelling, setIsCancelling] = useState(false);
     const [conversionProgress, setConversionProgress] = useState({ current: 0, total: 0, fileName: '' });
     const [showSettings, setShowSettings] = useState(false);
     const [showFilterSettings, setShowFilterSettings] = useState(false);
-    const [cacheInfo, setCacheInfo] = useState({ fileCount: 0, totalSizeBytes: 0 });
+    const [cacheInfo, setCacheInfo] = useState({ fileCount: 0, totalSizeBytes: 0, evictableBytes: 0 });
+    const [isMovingCache, setIsMovingCache] = useState(false);
+    const [mappings, setMappings] = useState([]);
+    const [mappingLabelDraft, setMappingLabelDraft] = useState('');
     // Track which files came from uasset conversion (for save flow)
     const [uassetSourceMap, setUassetSourceMap] = useState({});
 
@@ -482,8 +650,6 @@ function App() {
 
                             if (convResult.success && convResult.json_path) {
                                 try {
-                                    const content = await readTextFile(convResult.json_path);
-
                                     // Robust matching strategy
                                     let original = uassetFiles.find(u => u.fullPath === convResult.uasset_path);
                                     if (!original) {
@@ -497,7 +663,8 @@ function App() {
 
                                     fileObjects.push({
                                         name: convResult.file_name.replace(/\.uasset$/i, '.json'),
-                                        content: content,
+                                        jsonPath: convResult.json_path,
+                                        summary: convResult.summary,
                                         relativePath: relPath
                                     });
 
@@ -527,7 +694,7 @@ function App() {
                         });
                         await new Promise(resolve => setTimeout(resolve, 50));
                     }
-                    processFileObjects(fileObjects, colorParams.length > 0);
+                    await processFileObjects(fileObjects, colorParams.length > 0);
                 }
 
                 setIsConverting(false);
@@ -578,7 +745,7 @@ function App() {
             // Clear the backend cache so files are re-converted from originals
             try {
                 await invoke('clear_cache');
-                setCacheInfo({ fileCount: 0, totalSizeBytes: 0 });
+                setCacheInfo(prev => ({ ...prev, fileCount: 0, totalSizeBytes: 0, evictableBytes: 0 }));
                 addDebugLog('Cache cleared on reset');
             } catch (err) {
                 console.error('Failed to clear cache on reset:', err);
@@ -644,21 +811,25 @@ function App() {
     useEffect(() => {
         const loadSettings = async () => {
             try {
-                const loadedSettings = await invoke('get_settings');
+                const layered = await invoke('get_layered_settings');
+                const loadedSettings = layered.settings;
                 setSettings(loadedSettings);
-
-                // Use dictionary from settings if available, otherwise fallback to default
-                if (loadedSettings.filterDictionary) {
-                    setFilterDictionary(loadedSettings.filterDictionary);
-                    addDebugLog(`✓ Filter dictionary loaded from settings`);
+                setSettingLayers({ projectFile: layered.project_file, sources: layered.sources });
+
+                // Use the active profile's dictionary if available, otherwise fallback to default
+                const profiles = loadedSettings.filterProfiles;
+                if (profiles?.profiles?.[profiles.active]) {
+                    setFilterProfiles(profiles);
+                    setFilterDictionary(profiles.profiles[profiles.active]);
+                    addDebugLog(`✓ Filter profile "${profiles.active}" loaded from settings`);
                 } else {
                     setFilterDictionary(defaultFilterDictionary);
                     addDebugLog(`⚠ No dictionary in settings - using default`);
                 }
 
-                addDebugLog(`Settings loaded: usmap=${loadedSettings.usmapPath || 'not set'}`);
+                addDebugLog(`Settings loaded: usmap=${loadedSettings.activeMapping ? `library ${loadedSettings.activeMapping.slice(0, 12)}` : loadedSettings.usmapPath || 'not set'}`);
             } catch (err) {
-                addDebugLog(`Failed to load settings: ${err}`);
+                addDebugLog(`Failed to load settings: ${describeError(err)}`);
                 setFilterDictionary(defaultFilterDictionary);
             }
 
@@ -666,10 +837,19 @@ function App() {
                 const cache = await invoke('get_cache_info');
                 setCacheInfo({
                     fileCount: cache.file_count,
-                    totalSizeBytes: cache.total_size_bytes
+                    totalSizeBytes: cache.total_size_bytes,
+                    evictableBytes: cache.evictable_bytes,
+                    cacheDir: cache.cache_dir
                 });
             } catch (err) {
-                addDebugLog(`Failed to load cache info: ${err}`);
+                addDebugLog(`Failed to load cache info: ${describeError(err)}`);
+            }
+
+            try {
+                const list = await invoke('list_engine_versions');
+                setEngineVersions(list.versions);
+            } catch (err) {
+                addDebugLog(`Failed to list engine versions: ${describeError(err)}`);
             }
         };
         loadSettings();
@@ -708,179 +888,97 @@ function App() {
         };
     }, [handleUndo, handleRedo]);
 
-    // helper to decide if a param name matches the external filter dictionary
-    const paramMatchesFilter = (paramName) => {
-        if (!filterDictionary) return false; // don't match until dictionary is loaded
-
-        const paramNameLower = (paramName || '').toLowerCase();
-
-        const includeKeywords = (filterDictionary.include_keywords || []).map(k => String(k).toLowerCase());
-        const excludeKeywords = (filterDictionary.exclude_keywords || []).map(k => String(k).toLowerCase());
-
-        // include requires at least one include keyword (if include list empty => allow all)
-        const hasIncludeKeyword = includeKeywords.length === 0
-            ? true
-            : includeKeywords.some(keyword => paramNameLower.includes(keyword));
-
-        // substring exclusions (JSON-driven)
-        const hasExcludedKeyword = excludeKeywords.some(keyword => paramNameLower.includes(keyword));
-
-        // debug: show why a param is rejected
-        if (!hasIncludeKeyword || hasExcludedKeyword) {
-            console.debug('paramMatchesFilter: excluded', {
-                paramName,
-                hasIncludeKeyword,
-                hasExcludedKeyword,
-                includeKeywords,
-                excludeKeywords
-            });
+    // Keeps the params whose names pass the active filter profile. Rules are matched in Rust
+    const filterParamsByName = async (params) => {
+        const names = [...new Set(params.map(p => p.paramName))];
+        if (names.length === 0) return params;
+        try {
+            const decisions = await invoke('test_filter', { names });
+            const kept = new Set(decisions.filter(d => d.matched).map(d => d.name));
+            return params.filter(p => kept.has(p.paramName));
+        } catch (err) {
+            addDebugLog(`Filter failed, showing all parameters: ${describeError(err)}`);
+            return params;
         }
-
-        return hasIncludeKeyword && !hasExcludedKeyword;
     };
 
-    const getColorPropertyNames = () => {
-        return filterDictionary?.color_property_names || [
-            'ColorAndOpacity', 'SpecifiedColor', 'BaseColor', 'HighlightColor',
-            'FontTopColor', 'FontButtomColor', 'VectorParameter', 'ShadowColor',
-            'ContentColor', 'OutlineColor', 'Color', 'TextColor', 'BackgroundColor'
-        ];
+    // Builds params from a backend parameter summary, so the JSON itself is only read when saving
+    const extractColorsFromSummary = (summary, fileName, relativePath, allParams) => {
+        summary.params.forEach(param => {
+            allParams.push({
+                id: `${relativePath}#${param.pointer}`,
+                fileName,
+                paramName: param.param_name,
+                path: pointerToPath(param.pointer),
+                rgba: { ...param.rgba },
+                originalRgba: param.rgba,
+                sourceFormat: summary.asset_type,
+                relativePath
+            });
+        });
     };
 
-    const findColorsRecursive = (currentObject, currentPath, fileName, parentName, allParams, relativePath) => {
-        // Exit if the item is not a searchable object
-        if (!currentObject || typeof currentObject !== 'object') {
-            return;
-        }
-
-        // Determine which property names count as color properties via JSON
-        const colorNames = getColorPropertyNames();
-        const isColorProperty = colorNames.includes(currentObject.Name) && currentObject.StructType === 'LinearColor';
-
-        const colorValue = currentObject?.Value?.[0]?.Value;
-
-        if (isColorProperty && colorValue && typeof colorValue.R !== 'undefined') {
-            // Build a human-readable param name that matches the VectorParameter flow
-            const paramName = `${parentName} - ${currentObject.Name}`;
-
-            // Only add parameters that match the external filter dictionary
-            if (!paramMatchesFilter(paramName)) {
-                return;
-            }
-
-            const id = `${relativePath}-${parentName}-${currentObject.Name}-${allParams.length}`;
-            const path = [...currentPath, 'Value', 0, 'Value'];
-
-            const sanitizedRgba = {
-                ...colorValue,
-                R: parseFloat(colorValue.R) || 0,
-                G: parseFloat(colorValue.G) || 0,
-                B: parseFloat(colorValue.B) || 0,
-                A: parseFloat(colorValue.A) || 0,
-            };
-
-            allParams.push({ id, fileName, paramName, path, rgba: sanitizedRgba, relativePath });
-
-        } else {
-            // If it's not a color property, treat it as a generic container and search its children.
-            if (Array.isArray(currentObject)) {
-                currentObject.forEach((item, index) => {
-                    findColorsRecursive(item, [...currentPath, index], fileName, parentName, allParams, relativePath);
-                });
-            } else {
-                for (const key in currentObject) {
-                    if (Object.prototype.hasOwnProperty.call(currentObject, key)) {
-                        findColorsRecursive(currentObject[key], [...currentPath, key], fileName, parentName, allParams, relativePath);
-                    }
-                }
+    // Lists the colors of cached or opened JSON files in the backend, already filtered by the active profile
+    const extractColorParams = async (sources) => {
+        if (sources.length === 0) return [];
+        try {
+            const extraction = await invoke('extract_color_params', { sources });
+            if (extraction.failed.length > 0) {
+                extraction.failed.forEach(f => addDebugLog(`Failed to read ${f.relative_path}: ${describeError(f.error)}`));
+                alert(`Error processing ${extraction.failed.map(f => f.relative_path).join(', ')}.`);
             }
+            return extraction.params.map(param => ({
+                id: param.id,
+                fileName: param.file_name,
+                paramName: param.param_name,
+                path: pointerToPath(param.pointer),
+                rgba: { ...param.rgba },
+                originalRgba: param.rgba,
+                sourceFormat: param.source_format,
+                relativePath: param.relative_path
+            }));
+        } catch (err) {
+            addDebugLog(`Failed to extract color parameters: ${describeError(err)}`);
+            alert(`Failed to extract color parameters: ${describeError(err)}`);
+            return [];
         }
     };
 
-    // JSON format spider to find color parameters
-    const parseJsonAndExtractColors = (json, fileName, relativePath, allParams) => {
-        // FORMAT TYPE 1: VFX Material File (original format)
-        const vectorParamsArray = json?.Exports?.[0]?.Data?.find(p => p.Name === 'VectorParameterValues');
-        if (vectorParamsArray && vectorParamsArray.Value) {
-            vectorParamsArray.Value.forEach((param, paramIndex) => {
-                const paramInfo = param?.Value?.find(p => p.Name === 'ParameterInfo');
-                const paramName = paramInfo?.Value?.find(p => p.Name === 'Name')?.Value;
-
-                if (paramName) {
-                    // Use centralized JSON-driven matcher instead of any hardcoded lists
-                    if (!paramMatchesFilter(paramName)) return;
-
-                    const paramValueObj = param?.Value?.find(p => p.Name === 'ParameterValue');
-                    const linearColor = paramValueObj?.Value?.find(p => p.Name === 'ParameterValue')?.Value;
-
-                    if (linearColor) {
-                        const id = `${relativePath}-${paramName}-${paramIndex}`;
-                        const path = [
-                            'Exports', 0, 'Data',
-                            json.Exports[0].Data.findIndex(p => p.Name === 'VectorParameterValues'),
-                            'Value', paramIndex, 'Value',
-                            param.Value.findIndex(p => p.Name === 'ParameterValue'),
-                            'Value', 0, 'Value'
-                        ];
-
-                        const sanitizedRgba = {
-                            ...linearColor,
-                            R: parseFloat(linearColor.R) || 0,
-                            G: parseFloat(linearColor.G) || 0,
-                            B: parseFloat(linearColor.B) || 0,
-                            A: parseFloat(linearColor.A) || 0,
-                        };
-
-                        allParams.push({ id, fileName, paramName, path, rgba: sanitizedRgba, relativePath });
-                    }
-                }
-            });
-        }
-        // FORMAT TYPE 2: RichText blueprints support
-        else if (json?.Exports?.[0]?.$type === "UAssetAPI.ExportTypes.DataTableExport, UAssetAPI" && json.Exports[0].Table?.Data) {
-            const tableData = json.Exports[0].Table.Data;
-            const tablePath = ['Exports', 0, 'Table', 'Data'];
-
-            tableData.forEach((row, rowIndex) => {
-                if (row.StructType === "RichTextStyleRow") {
-                    const styleName = row.Name;
-                    const rowPath = [...tablePath, rowIndex, 'Value'];
-                    findColorsRecursive(row.Value, rowPath, fileName, styleName, allParams, relativePath);
-                }
-            });
-        }
-        // FORMAT TYPE 3: Generic? Blueprint support
-        else if (Array.isArray(json?.Exports)) {
-            json.Exports.forEach((exportItem, exportIndex) => {
-                if (Array.isArray(exportItem.Data)) {
-                    // Uses export's ObjectName as a descriptive parent name
-                    const parentName = exportItem.ObjectName || `Export_${exportIndex}`;
-                    const basePath = ['Exports', exportIndex, 'Data'];
-                    // recursive search within the Data array of this export
-                    findColorsRecursive(exportItem.Data, basePath, fileName, parentName, allParams, relativePath);
-                }
-            });
-        }
-    }
-
-    const processFileObjects = (fileObjects, append = false) => {
-        let allParams = append ? [...colorParams] : [];
+    const processFileObjects = async (fileObjects, append = false) => {
+        const foundParams = [];
+        const sources = [];
+        const fileOrder = new Map();
         let newOriginalFiles = append ? { ...originalFiles } : {};
 
         fileObjects.forEach(fileObj => {
-            if (append && newOriginalFiles[fileObj.relativePath]) {
+            if (append && fileObj.relativePath in newOriginalFiles) {
+                return;
+            }
+            fileOrder.set(fileObj.relativePath, fileOrder.size);
+            if (fileObj.summary || fileObj.content == null) {
+                // Placeholder until the JSON is needed, see loadOriginalFiles
+                newOriginalFiles[fileObj.relativePath] = null;
+                if (fileObj.summary) {
+                    extractColorsFromSummary(fileObj.summary, fileObj.name, fileObj.relativePath, foundParams);
+                } else {
+                    sources.push({ relative_path: fileObj.relativePath, json_path: fileObj.jsonPath });
+                }
                 return;
             }
             try {
-                const json = JSON.parse(fileObj.content);
-                newOriginalFiles[fileObj.relativePath] = json;
-                parseJsonAndExtractColors(json, fileObj.name, fileObj.relativePath, allParams);
+                newOriginalFiles[fileObj.relativePath] = JSON.parse(fileObj.content);
+                sources.push({ relative_path: fileObj.relativePath, content: fileObj.content });
             } catch (error) {
                 console.error("Error processing file content:", fileObj.name, error);
                 alert(`Error processing file ${fileObj.name}.`);
             }
         });
 
+        // Only add parameters that match the filter profile, keeping the files in load order
+        const newParams = [...await filterParamsByName(foundParams), ...await extractColorParams(sources)]
+            .sort((a, b) => fileOrder.get(a.relativePath) - fileOrder.get(b.relativePath));
+        const allParams = [...(append ? colorParams : []), ...newParams];
+
         //  Extract folder list for filtering UI
         const uniqueFolders = [...new Set(allParams.map(p => {
             const lastSlash = p.relativePath.lastIndexOf('/');
@@ -925,7 +1023,7 @@ function App() {
         });
 
         await Promise.all(promises);
-        processFileObjects(fileObjects, append);
+        await processFileObjects(fileObjects, append);
     };
 
     const handleFileChange = (event) => {
@@ -936,25 +1034,149 @@ function App() {
     // === OPEN SETTINGS AND REFRESH CACHE INFO ===
     const handleOpenSettings = async () => {
         setShowSettings(true);
+        refreshMappings();
         try {
             const cache = await invoke('get_cache_info');
             setCacheInfo({
                 fileCount: cache.file_count,
-                totalSizeBytes: cache.total_size_bytes
+                totalSizeBytes: cache.total_size_bytes,
+                evictableBytes: cache.evictable_bytes,
+                cacheDir: cache.cache_dir
             });
         } catch (err) {
-            addDebugLog(`Failed to refresh cache info: ${err}`);
+            addDebugLog(`Failed to refresh cache info: ${describeError(err)}`);
         }
     };
 
-    // === UASSET FOLDER SELECTION VIA TAURI DIALOG ===
-    const handleSelectUassetFolder = async () => {
-        if (!settings.usmapPath) {
-            alert('Please set a .usmap file path in Settings first!');
-            handleOpenSettings();
+    // === MAPPINGS LIBRARY ===
+    const refreshMappings = async () => {
+        try {
+            setMappings(await invoke('list_mappings'));
+        } catch (err) {
+            addDebugLog(`Failed to list mappings: ${describeError(err)}`);
+        }
+    };
+
+    const handleImportMapping = async () => {
+        const path = await openDialog({
+            filters: [{ name: 'Usmap', extensions: ['usmap'] }],
+            multiple: false,
+            title: 'Import .usmap into the library'
+        });
+        if (!path) return;
+        try {
+            const label = mappingLabelDraft.trim() || null;
+            const result = await invoke('import_mapping', { path, label });
+            const { label: imported, hash } = result.mapping;
+            addDebugLog(result.duplicate
+                ? `Mapping already in the library as "${imported}"`
+                : `Imported mapping "${imported}" (${hash.slice(0, 12)})`);
+            setMappingLabelDraft('');
+            await refreshMappings();
+        } catch (err) {
+            alert(`Failed to import mapping: ${describeError(err)}`);
+        }
+    };
+
+    // Converts with a library mapping, or with the .usmap file path again for null
+    const handleUseMapping = async (mapping) => {
+        try {
+            await invoke('set_active_mapping', { name: mapping?.hash ?? null });
+            setSettings(prev => ({ ...prev, activeMapping: mapping?.hash ?? null }));
+            addDebugLog(mapping ? `Converting with mapping "${mapping.label}"` : 'Converting with the .usmap file path');
+            await refreshMappings();
+        } catch (err) {
+            alert(`Failed to switch mapping: ${describeError(err)}`);
+        }
+    };
+
+    const handleRelabelMapping = async (mapping) => {
+        const label = mappingLabelDraft.trim();
+        if (!label) {
+            alert('Enter the new label first.');
             return;
         }
+        try {
+            await invoke('relabel_mapping', { name: mapping.hash, label });
+            setMappingLabelDraft('');
+            await refreshMappings();
+        } catch (err) {
+            alert(`Failed to relabel mapping: ${describeError(err)}`);
+        }
+    };
+
+    const handleDeleteMapping = async (mapping) => {
+        if (!confirm(`Delete mapping "${mapping.label}" from the library?`)) return;
+        try {
+            await invoke('delete_mapping', { name: mapping.hash });
+            await refreshMappings();
+        } catch (err) {
+            alert(`Failed to delete mapping: ${describeError(err)}`);
+        }
+    };
+
+    const handleInstallMapping = async (mapping) => {
+        try {
+            const target = await invoke('install_mapping_for_uassetgui', { name: mapping.hash });
+            addDebugLog(`Installed mapping "${mapping.label}" for UAssetGUI at ${target}`);
+        } catch (err) {
+            alert(`Failed to install mapping: ${describeError(err)}`);
+        }
+    };
+
+    // === MOVE CACHE TO ANOTHER FOLDER ===
+    const handleMoveCache = async (location) => {
+        setIsMovingCache(true);
+        try {
+            const report = await invoke('set_cache_location', { location });
+            setSettings(prev => ({ ...prev, cacheLocation: location }));
+            addDebugLog(`Cache moved to ${report.cache_dir}: ${report.moved_files} files, ${report.rewritten_entries} index entries updated`);
+            await handleOpenSettings();
+        } catch (err) {
+            alert(`Failed to move cache: ${describeError(err)}`);
+        } finally {
+            setIsMovingCache(false);
+        }
+    };
+
+    // Cache bundles let teammates share conversions of the same game files
+    const handleExportCacheBundle = async () => {
+        try {
+            const { save } = window.__TAURI__.dialog;
+            const path = await save({
+                title: 'Export Cache Bundle',
+                defaultPath: 'conversion-cache.rvfxc',
+                filters: [{ name: 'RVFX Cache Bundle', extensions: ['rvfxc'] }]
+            });
+            if (!path) return;
+            const report = await invoke('export_cache_bundle', { path });
+            const sizeMb = (report.bytes / 1024 / 1024).toFixed(1);
+            addDebugLog(`Exported ${report.entries} cache entries (${sizeMb} MB) to ${report.bundle_path}`);
+            alert(`Exported ${report.entries} cached conversions.`);
+        } catch (err) {
+            alert(`Failed to export cache: ${describeError(err)}`);
+        }
+    };
+
+    const handleImportCacheBundle = async () => {
+        try {
+            const path = await openDialog({
+                title: 'Import Cache Bundle',
+                multiple: false,
+                filters: [{ name: 'RVFX Cache Bundle', extensions: ['rvfxc'] }]
+            });
+            if (!path) return;
+            const report = await invoke('import_cache_bundle', { path });
+            addDebugLog(`Imported ${report.entries} cache entries, ${report.skipped} already cached`);
+            alert(`Imported ${report.entries} cached conversions (${report.skipped} already cached).`);
+            await handleOpenSettings();
+        } catch (err) {
+            alert(`Failed to import cache: ${describeError(err)}`);
+        }
+    };
 
+    // === UASSET FOLDER SELECTION VIA TAURI DIALOG ===
+    const handleSelectUassetFolder = async () => {
         try {
             // Open folder dialog
             const selectedPath = await openDialog({
@@ -966,11 +1188,19 @@ function App() {
             if (!selectedPath) return;
 
             addDebugLog(`Selected folder: ${selectedPath}`);
+
+            // The folder's own settings may provide the .usmap
+            const effectiveSettings = await openProjectFolder(selectedPath);
+            if (!hasMapping(effectiveSettings)) {
+                alert('Please set a .usmap file path in Settings first!');
+                handleOpenSettings();
+                return;
+            }
             setIsConverting(true);
             setConversionProgress({ current: 0, total: 0, fileName: 'Scanning...' });
 
             // Use backend to find and convert uasset files
-            const { readDir, readTextFile } = window.__TAURI__.fs;
+            const { readDir } = window.__TAURI__.fs;
 
             // Recursively find all .uasset files (manual recursion for consistency)
             const findUassetFiles = async (rootDir) => {
@@ -1052,7 +1282,20 @@ function App() {
                 });
 
                 addDebugLog(`Conversion complete: ${result.succeeded}/${result.total} succeeded, ${result.cached_count} from cache`);
+                if (result.cancelled) {
+                    addDebugLog(`Conversion cancelled: ${result.results.filter(r => r.aborted).length} files aborted`);
+                }
                 addDebugLog(`Results array has ${result.results?.length || 0} items`);
+                const missReasons = {};
+                result.results.forEach(r => {
+                    if (r.cache_miss) missReasons[r.cache_miss] = (missReasons[r.cache_miss] || 0) + 1;
+                });
+                if (Object.keys(missReasons).length > 0) {
+                    addDebugLog(`Cache misses: ${Object.entries(missReasons).map(([reason, n]) => `${reason} x${n}`).join(', ')}`);
+                }
+                result.results
+                    .filter(r => !r.success && !r.aborted && r.error)
+                    .forEach(r => addDebugLog(`Failed to convert ${r.file_name}: ${describeError(r.error, { details: settings.showDetailedErrors })}`));
 
                 // Process the converted JSONs IN PARALLEL for speed
                 const fileObjects = [];
@@ -1063,8 +1306,6 @@ function App() {
                     .filter(convResult => convResult.success && convResult.json_path)
                     .map(async (convResult) => {
                         try {
-                            const jsonContent = await readTextFile(convResult.json_path);
-
                             // Try to find the original file object using full path first (most reliable)
                             let originalFile = uassetFiles.find(f => f.fullPath === convResult.uasset_path);
 
@@ -1079,7 +1320,8 @@ function App() {
                             return {
                                 fileObject: {
                                     name: convResult.file_name.replace(/\.uasset$/i, '.json'),
-                                    content: jsonContent,
+                                    jsonPath: convResult.json_path,
+                                    summary: convResult.summary,
                                     relativePath: keyPath
                                 },
                                 sourceEntry: {
@@ -1115,7 +1357,7 @@ function App() {
                     }
                 }
 
-                addDebugLog(`Read ${fileObjects.length} JSON files in parallel`);
+                addDebugLog(`${fileObjects.filter(f => !f.summary).length} JSON files to scan, ${fileObjects.filter(f => f.summary).length} loaded from parameter summaries`);
 
                 setUassetSourceMap(newSourceMap);
                 addDebugLog(`fileObjects.length = ${fileObjects.length}`);
@@ -1130,7 +1372,7 @@ function App() {
                 await new Promise(resolve => setTimeout(resolve, 50));
 
                 if (fileObjects.length > 0) {
-                    processFileObjects(fileObjects, colorParams.length > 0);
+                    await processFileObjects(fileObjects, colorParams.length > 0);
                     addDebugLog(`Loaded ${fileObjects.length} files into editor`);
                 } else {
                     addDebugLog('WARNING: No fileObjects to load!');
@@ -1140,7 +1382,9 @@ function App() {
                 const cache = await invoke('get_cache_info');
                 setCacheInfo({
                     fileCount: cache.file_count,
-                    totalSizeBytes: cache.total_size_bytes
+                    totalSizeBytes: cache.total_size_bytes,
+                    evictableBytes: cache.evictable_bytes,
+                    cacheDir: cache.cache_dir
                 });
 
             } finally {
@@ -1148,9 +1392,9 @@ function App() {
             }
 
         } catch (err) {
-            addDebugLog(`Error selecting folder: ${err}`);
+            addDebugLog(`Error selecting folder: ${describeError(err)}`);
             if (settings.showDetailedErrors) {
-                alert(`Error: ${err}`);
+                alert(`Error: ${describeError(err)}`);
             } else {
                 alert('Failed to load uasset files. Check the debug log for details.');
             }
@@ -1334,6 +1578,27 @@ function App() {
     };
 
 
+    // Copies of the original JSONs to apply edits to, reading summary-loaded files from the cache
+    const loadOriginalFiles = async (relativePaths) => {
+        const files = {};
+        await Promise.all(relativePaths.map(async (relativePath) => {
+            const original = originalFiles[relativePath];
+            if (original) {
+                files[relativePath] = JSON.parse(JSON.stringify(original));
+                return;
+            }
+            const jsonPath = uassetSourceMap[relativePath]?.jsonPath;
+            if (!jsonPath) return;
+            try {
+                const { readTextFile } = window.__TAURI__.fs;
+                files[relativePath] = JSON.parse(await readTextFile(jsonPath));
+            } catch (err) {
+                addDebugLog(`Failed to read JSON for ${relativePath}: ${err}`);
+            }
+        }));
+        return files;
+    };
+
     const handleSave = async () => {
         if (colorParams.length === 0) {
             alert("No parameters to save.");
@@ -1342,12 +1607,12 @@ function App() {
 
         setSaveStatus('Saving...');
 
-        const modifiedFiles = JSON.parse(JSON.stringify(originalFiles));
+        const modifiedFiles = await loadOriginalFiles(Object.keys(originalFiles));
         colorParams.forEach(param => {
             // Use relativePath to get the right path
             const fileToModify = modifiedFiles[param.relativePath];
             if (fileToModify) {
-                setNestedValue(fileToModify, param.path, param.rgba);
+                applyParamColor(fileToModify, param);
             }
         });
 
@@ -1413,17 +1678,21 @@ function App() {
             // Determine which files have actually been edited
             const editedFilePaths = new Set();
             colorParams.forEach(param => {
-                const originalFile = originalFiles[param.relativePath];
-                if (!originalFile) return;
-
-                // Get the original value at this path
-                let originalValue = originalFile;
-                for (const key of param.path) {
-                    if (originalValue && typeof originalValue === 'object') {
-                        originalValue = originalValue[key];
-                    } else {
-                        originalValue = undefined;
-                        break;
+                // Params carry their original value; fall back to looking it up in the file
+                let originalValue = param.originalRgba;
+                if (!originalValue) {
+                    const originalFile = originalFiles[param.relativePath];
+                    if (!originalFile) return;
+
+                    // Get the original value at this path
+                    originalValue = originalFile;
+                    for (const key of param.path) {
+                        if (originalValue && typeof originalValue === 'object') {
+                            originalValue = originalValue[key];
+                        } else {
+                            originalValue = undefined;
+                            break;
+                        }
                     }
                 }
 
@@ -1464,11 +1733,11 @@ function App() {
             const { writeTextFile } = window.__TAURI__.fs;
 
             // First, apply edits to the cached JSON files
-            const modifiedFiles = JSON.parse(JSON.stringify(originalFiles));
+            const modifiedFiles = await loadOriginalFiles(filesToSave);
             colorParams.forEach(param => {
                 const fileToModify = modifiedFiles[param.relativePath];
                 if (fileToModify) {
-                    setNestedValue(fileToModify, param.path, param.rgba);
+                    applyParamColor(fileToModify, param);
                 }
             });
 
@@ -1547,23 +1816,42 @@ function App() {
 
                 addDebugLog(`Result: ${JSON.stringify(result)}`);
                 addDebugLog(`Conversion complete: ${result.succeeded}/${result.total} succeeded`);
+                result.results
+                    .filter(r => !r.success && !r.aborted)
+                    .forEach(r => addDebugLog(`Failed to save ${r.file_name}: ${describeError(r.error, { details: settings.showDetailedErrors })}`));
+
+                // Round-trip reports are only present when verification is enabled
+                const unverified = result.results.filter(r => r.round_trip && (r.round_trip.error || r.round_trip.mismatch_count > 0));
+                unverified.forEach(r => {
+                    if (r.round_trip.error) {
+                        addDebugLog(`Verify failed for ${r.file_name}: ${describeError(r.round_trip.error)}`);
+                        return;
+                    }
+                    addDebugLog(`Verify failed for ${r.file_name}: ${r.round_trip.mismatch_count} mismatched properties`);
+                    r.round_trip.mismatches.forEach(m =>
+                        addDebugLog(`  ${m.pointer}: expected ${JSON.stringify(m.expected ?? '(missing)')}, got ${JSON.stringify(m.actual ?? '(missing)')}`));
+                });
 
-                if (result.succeeded > 0) {
+                if (result.cancelled) {
+                    setSaveStatus(`Cancelled after saving ${result.succeeded} .uasset files.`);
+                } else if (unverified.length > 0) {
+                    setSaveStatus(`Saved ${result.succeeded} .uasset files, but ${unverified.length} failed verification. Check debug log for details.`);
+                } else if (result.succeeded > 0) {
                     setSaveStatus(`Saved ${result.succeeded} .uasset files to output folder!`);
                 } else {
                     setSaveStatus(`Conversion failed. Check debug log for details.`);
                 }
 
             } catch (invokeErr) {
-                addDebugLog(`Invoke error: ${invokeErr}`);
+                addDebugLog(`Invoke error: ${describeError(invokeErr, { details: true })}`);
                 throw invokeErr;
             } finally {
                 unlisten();
             }
 
         } catch (err) {
-            addDebugLog(`Error saving UAsset files: ${err}`);
-            setSaveStatus(`Error: ${err}`);
+            addDebugLog(`Error saving UAsset files: ${describeError(err)}`);
+            setSaveStatus(`Error: ${describeError(err)}`);
         } finally {
             setIsConverting(false);
             setConversionProgress({ current: 0, total: 0, fileName: '' });
@@ -1580,13 +1868,16 @@ function App() {
             return;
         }
 
-        const sessionData = colorParams
-            .filter(p => selectedParams.has(p.id))
-            .map(p => ({
-                relativePath: p.relativePath.replace(/\.json$/i, ''),
-                paramName: p.paramName,
-                rgba: p.rgba
-            }));
+        const sessionData = {
+            filterProfile: filterProfiles.active,
+            params: colorParams
+                .filter(p => selectedParams.has(p.id))
+                .map(p => ({
+                    relativePath: p.relativePath.replace(/\.json$/i, ''),
+                    paramName: p.paramName,
+                    rgba: p.rgba
+                }))
+        };
 
         const jsonString = JSON.stringify(sessionData, null, 2);
 
@@ -1612,7 +1903,7 @@ function App() {
 
         } catch (err) {
             console.error("Failed to export session:", err);
-            alert(`Failed to export session: ${err.message || err}`);
+            alert(`Failed to export session: ${describeError(err)}`);
         }
     };
 
@@ -1634,12 +1925,13 @@ function App() {
             if (!filePath) return; // User cancelled
 
             const content = await readTextFile(filePath);
-            const sessionData = JSON.parse(content);
+            const project = readProjectFile(JSON.parse(content));
 
-            if (!Array.isArray(sessionData)) {
+            if (!project) {
                 alert("Invalid project file format.");
                 return;
             }
+            const sessionData = project.params;
 
             addDebugLog(`Importing project with ${sessionData.length} entries`);
 
@@ -1717,16 +2009,17 @@ function App() {
 
             recordHistory(newColorParams);
             addDebugLog(`Updated ${updatedCount} parameters from import`);
+            const profileNote = await applyPinnedFilterProfile(project.filterProfile);
 
             if (updatedCount > 0) {
-                alert(`Project imported successfully! ${updatedCount} parameters were updated.`);
+                alert(`Project imported successfully! ${updatedCount} parameters were updated.${profileNote}`);
             } else {
-                alert(`Project file loaded but no matching parameters found. The file structure may not match the currently loaded assets.`);
+                alert(`Project file loaded but no matching parameters found. The file structure may not match the currently loaded assets.${profileNote}`);
             }
 
         } catch (err) {
             console.error("Failed to import session:", err);
-            alert(`Failed to import session: ${err.message || err}`);
+            alert(`Failed to import session: ${describeError(err)}`);
         }
     };
 
@@ -1736,14 +2029,15 @@ function App() {
         if (!file) return;
 
         const reader = new FileReader();
-        reader.onload = (e) => {
+        reader.onload = async (e) => {
             try {
-                const sessionData = JSON.parse(e.target.result);
+                const project = readProjectFile(JSON.parse(e.target.result));
 
-                if (!Array.isArray(sessionData)) {
+                if (!project) {
                     alert("Invalid project file format.");
                     return;
                 }
+                const sessionData = project.params;
 
                 const getFileName = (path) => {
                     if (!path) return '';
@@ -1813,11 +2107,12 @@ function App() {
                 });
 
                 recordHistory(newParams);
+                const profileNote = await applyPinnedFilterProfile(project.filterProfile);
 
                 if (updatedCount > 0) {
-                    alert(`Project imported successfully! ${updatedCount} parameters were updated.`);
+                    alert(`Project imported successfully! ${updatedCount} parameters were updated.${profileNote}`);
                 } else {
-                    alert(`Project file loaded but no matching parameters found.`);
+                    alert(`Project file loaded but no matching parameters found.${profileNote}`);
                 }
 
             } catch (error) {
@@ -1846,13 +2141,164 @@ function App() {
         // Also update backend settings
         try {
             await invoke('set_filter_dictionary', { dictionary: newDictionary });
-            setSettings(prev => ({ ...prev, filterDictionary: newDictionary }));
+            const profiles = {
+                ...filterProfiles,
+                profiles: { ...filterProfiles.profiles, [filterProfiles.active]: newDictionary }
+            };
+            setFilterProfiles(profiles);
+            setSettings(prev => ({ ...prev, filterProfiles: profiles }));
         } catch (err) {
             console.error("Failed to save filter dictionary:", err);
-            addDebugLog(`Failed to save filter settings: ${err}`);
+            addDebugLog(`Failed to save filter settings: ${describeError(err)}`);
+        }
+    };
+
+    // Shows which rule keeps or drops each name, using the dictionary as edited
+    const handleTestFilter = async () => {
+        const names = filterTestNames.split('\n').map(n => n.trim()).filter(Boolean);
+        try {
+            setFilterTestResults(await invoke('test_filter', { names, dictionary: filterDictionary }));
+        } catch (err) {
+            setFilterTestResults({ error: describeError(err) });
+        }
+    };
+
+    const describeFilterRule = (rule) => {
+        if (!rule) return 'no rule matched';
+        const list = rule.source === 'rules' ? 'rule' : rule.source.replace('_', ' ').replace(/s$/, '');
+        return `${list} ${rule.index + 1}: ${rule.kind} "${rule.pattern}" (${rule.action}, priority ${rule.priority})`;
+    };
+
+    // === PROJECT SETTINGS ===
+    const applyLayeredSettings = (layered) => {
+        setSettings(layered.settings);
+        setSettingLayers({ projectFile: layered.project_file, sources: layered.sources });
+        const profiles = layered.settings.filterProfiles;
+        if (profiles?.profiles?.[profiles.active]) {
+            setFilterProfiles(profiles);
+            setFilterDictionary(profiles.profiles[profiles.active]);
         }
     };
 
+    // Layers the folder's .rvfxe.json over the global settings and returns the effective settings
+    const openProjectFolder = async (path) => {
+        try {
+            const layered = await invoke('open_project_folder', { path });
+            applyLayeredSettings(layered);
+            const overridden = Object.values(layered.sources).filter(source => source === 'project').length;
+            if (overridden > 0) {
+                addDebugLog(`Project settings: ${overridden} value(s) from ${layered.project_file}`);
+            }
+            return layered.settings;
+        } catch (err) {
+            addDebugLog(`Failed to read project settings: ${describeError(err)}`);
+            try {
+                const layered = await invoke('get_layered_settings');
+                applyLayeredSettings(layered);
+                return layered.settings;
+            } catch (e) {
+                return settings;
+            }
+        }
+    };
+
+    const handleProjectOverrideToggle = async (key, enabled) => {
+        try {
+            applyLayeredSettings(await invoke('set_project_override', { key, enabled }));
+        } catch (err) {
+            alert(`Failed to update project settings: ${describeError(err)}`);
+        }
+    };
+
+    // === FILTER PROFILES ===
+    const applyFilterProfiles = (profiles) => {
+        setFilterProfiles(profiles);
+        setFilterDictionary(profiles.profiles[profiles.active] || defaultFilterDictionary);
+        setSettings(prev => ({ ...prev, filterProfiles: profiles }));
+    };
+
+    // Runs a profile command; they all answer with the updated profiles
+    const runFilterProfileCommand = async (command, args) => {
+        try {
+            const profiles = await invoke(command, args);
+            applyFilterProfiles(profiles);
+            addDebugLog(`Filter profile "${profiles.active}" active (${command})`);
+            return true;
+        } catch (err) {
+            alert(`Filter profile: ${describeError(err)}`);
+            return false;
+        }
+    };
+
+    const handleCreateFilterProfile = async () => {
+        if (await runFilterProfileCommand('create_filter_profile', { name: profileNameDraft })) {
+            setProfileNameDraft('');
+        }
+    };
+
+    const handleDuplicateFilterProfile = async () => {
+        const newName = profileNameDraft.trim() || `${filterProfiles.active} copy`;
+        if (await runFilterProfileCommand('duplicate_filter_profile', { name: filterProfiles.active, newName })) {
+            setProfileNameDraft('');
+        }
+    };
+
+    const handleRenameFilterProfile = async () => {
+        if (await runFilterProfileCommand('rename_filter_profile', { name: filterProfiles.active, newName: profileNameDraft })) {
+            setProfileNameDraft('');
+        }
+    };
+
+    const handleDeleteFilterProfile = async () => {
+        await runFilterProfileCommand('delete_filter_profile', { name: filterProfiles.active });
+    };
+
+    // A linked profile follows later changes to the file, e.g. one a team shares
+    const handleImportFilterProfile = async (link) => {
+        const path = await openDialog({
+            title: link ? 'Link Filter Profile' : 'Import Filter Profile',
+            multiple: false,
+            filters: [{ name: 'Filter Dictionary', extensions: ['json'] }]
+        });
+        if (!path) return;
+        await runFilterProfileCommand('import_filter_profile', { path, link });
+    };
+
+    const handleExportFilterProfile = async () => {
+        try {
+            const { save } = window.__TAURI__.dialog;
+            const path = await save({
+                title: 'Export Filter Profile',
+                defaultPath: `${filterProfiles.active}.json`,
+                filters: [{ name: 'Filter Dictionary', extensions: ['json'] }]
+            });
+            if (!path) return;
+            await invoke('export_filter_profile', { name: filterProfiles.active, path });
+            addDebugLog(`Exported filter profile "${filterProfiles.active}" to ${path}`);
+        } catch (err) {
+            alert(`Failed to export filter profile: ${describeError(err)}`);
+        }
+    };
+
+    // Project files are a bare list of params, or since filter profiles an
+    // object that also pins the profile the project was made with
+    const readProjectFile = (data) => {
+        if (Array.isArray(data)) return { filterProfile: null, params: data };
+        if (data && Array.isArray(data.params)) return { filterProfile: data.filterProfile || null, params: data.params };
+        return null;
+    };
+
+    // Switches to the project's pinned profile; returns a note for the user
+    const applyPinnedFilterProfile = async (name) => {
+        if (!name || name === filterProfiles.active) return '';
+        if (!filterProfiles.profiles[name]) {
+            addDebugLog(`⚠ Project uses filter profile "${name}", which does not exist here`);
+            return `\n\nThis project was made with filter profile "${name}", which you don't have.`;
+        }
+        if (!await runFilterProfileCommand('switch_filter_profile', { name })) return '';
+        return `\n\nSwitched to the project's filter profile "${name}". Reload the assets to apply it.`;
+    };
+
     const handleFolderToggle = (folder) => {
         setSelectedFolders(prev => {
             const newSet = new Set(prev);
@@ -1977,7 +2423,7 @@ function App() {
                         className="absolute top-1 right-12 p-2 rounded-full text-gray-400 hover:text-white hover:bg-white/10 transition-colors z-10"
                     >
                         <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"><circle cx="12" cy="12" r="3"></circle><path d="M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z"></path></svg>
-                        {!settings.usmapPath && (
+                        {!hasMapping(settings) && (
                             <span className="absolute top-2 right-2 flex h-3 w-3">
                                 <span className="animate-ping absolute inline-flex h-full w-full rounded-full bg-red-400 opacity-75"></span>
                                 <span className="relative inline-flex rounded-full h-3 w-3 bg-red-500"></span>
@@ -2333,14 +2779,14 @@ function App() {
                             <StyledPanel title="Load Files">
                                 <div
                                     className={`text-center py-20 px-6 border-2 border-dashed transition-colors`}
-                                    style={{ backgroundColor: 'var(--bg-2)', borderColor: isDragging ? 'var(--accent-main)' : 'var(--bg-1)', opacity: settings.usmapPath ? 1 : 0.6 }}
-                                    onDragOver={settings.usmapPath ? handleDragOver : undefined}
-                                    onDragLeave={settings.usmapPath ? handleDragLeave : undefined}
-                                    onDrop={settings.usmapPath ? handleDrop : undefined}
+                                    style={{ backgroundColor: 'var(--bg-2)', borderColor: isDragging ? 'var(--accent-main)' : 'var(--bg-1)', opacity: hasMapping(settings) ? 1 : 0.6 }}
+                                    onDragOver={hasMapping(settings) ? handleDragOver : undefined}
+                                    onDragLeave={hasMapping(settings) ? handleDragLeave : undefined}
+                                    onDrop={hasMapping(settings) ? handleDrop : undefined}
                                 >
                                     <div
-                                        onClick={settings.usmapPath ? handleSelectUassetFolder : undefined}
-                                        className={`w-full h-full flex flex-col items-center justify-center ${settings.usmapPath ? 'cursor-pointer' : 'cursor-not-allowed'}`}
+                                        onClick={hasMapping(settings) ? handleSelectUassetFolder : undefined}
+                                        className={`w-full h-full flex flex-col items-center justify-center ${hasMapping(settings) ? 'cursor-pointer' : 'cursor-not-allowed'}`}
                                     >
                                         <svg className="mx-auto h-12 w-12" style={{ color: 'var(--text-4)' }} viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
                                             <path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8z"></path>
@@ -2352,8 +2798,8 @@ function App() {
                                             <h3 className="text-lg font-medium" style={{ color: 'var(--text-2)' }}>No files loaded</h3>
                                             <img src="./assets/images/shrug.png" alt="shrug emoji" className="h-6 w-6" />
                                         </div>
-                                        <p className="mt-1 text-sm font-medium" style={{ color: settings.usmapPath ? 'var(--text-4)' : 'var(--accent-warning, #f59e0b)' }}>
-                                            {settings.usmapPath
+                                        <p className="mt-1 text-sm font-medium" style={{ color: hasMapping(settings) ? 'var(--text-4)' : 'var(--accent-warning, #f59e0b)' }}>
+                                            {hasMapping(settings)
                                                 ? "Drag and drop .uasset files or click here to browse."
                                                 : "Set a valid mapping file in the settings to proceed"}
                                         </p>
@@ -2370,7 +2816,7 @@ function App() {
             {
                 showSettings && (
                     <div className="fixed inset-0 z-50 flex items-center justify-center" style={{ backgroundColor: 'rgba(0,0,0,0.7)' }}>
-                        <div className="w-full max-w-md p-6 shadow-xl border-2 relative group" style={{ backgroundColor: 'var(--bg-3)', borderColor: 'var(--bg-2)' }}>
+                        <div className="w-full max-w-md p-6 shadow-xl border-2 relative group max-h-[90vh] overflow-y-auto" style={{ backgroundColor: 'var(--bg-3)', borderColor: 'var(--bg-2)' }}>
                             <div className="absolute inset-0 border-2 pointer-events-none opacity-0 group-hover:opacity-100 transition-opacity" style={{ borderColor: 'var(--accent-main)', zIndex: 10 }}></div>
                             <div className="flex justify-between items-center mb-6">
                                 <h2 className="text-xl font-bold" style={{ color: 'var(--text-1)' }}>Settings</h2>
@@ -2402,8 +2848,116 @@ function App() {
                                                     title: 'Select .usmap mapping file'
                                                 });
                                                 if (path) {
-                                                    await invoke('set_usmap_path', { path });
-                                                    setSettings(prev => ({ ...prev, usmapPath: path }));
+                                                    try {
+                                                        await invoke('set_usmap_path', { path });
+                                                        setSettings(prev => ({ ...prev, usmapPath: path, activeMapping: null }));
+                                                        refreshMappings();
+                                                    } catch (err) {
+                                                        alert(`Invalid .usmap file: ${describeError(err)}`);
+                                                    }
+                                                }
+                                            }}
+                                            className="px-4 py-2 font-medium rounded-none"
+                                            style={{ backgroundColor: 'var(--accent-main)', color: 'var(--bg-4)' }}
+                                        >
+                                            Browse
+                                        </button>
+                                    </div>
+                                    {settings.activeMapping && (
+                                        <p className="text-xs mt-1 opacity-50 italic" style={{ color: 'var(--text-3)' }}>
+                                            Not used while a library mapping is active.
+                                        </p>
+                                    )}
+                                </div>
+
+                                {/* Mappings Library */}
+                                <div>
+                                    <label className="block text-sm font-medium mb-1" style={{ color: 'var(--text-2)' }}>
+                                        Mappings Library
+                                    </label>
+                                    <div className="space-y-1 mb-2">
+                                        {mappings.length === 0 && (
+                                            <p className="text-xs" style={{ color: 'var(--text-3)' }}>No mappings imported yet.</p>
+                                        )}
+                                        {mappings.map(mapping => (
+                                            <div key={mapping.hash} className="flex items-center gap-2 px-2 py-1 text-xs" style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}>
+                                                <span className="font-medium" style={{ color: mapping.active ? 'var(--accent-main)' : 'var(--text-2)' }}>
+                                                    {mapping.active ? '● ' : ''}{mapping.label}
+                                                </span>
+                                                <span className="flex-grow truncate" style={{ color: 'var(--text-3)' }} title={mapping.path}>
+                                                    {mapping.summary
+                                                        ? `${mapping.summary.version} (v${mapping.summary.versionNumber}) · ${mapping.summary.structCount} structs · ${mapping.hash.slice(0, 12)}`
+                                                        : `${mapping.error} · ${mapping.hash.slice(0, 12)}`}
+                                                </span>
+                                                {[
+                                                    [mapping.active ? 'Stop Using' : 'Use', () => handleUseMapping(mapping.active ? null : mapping)],
+                                                    ['Relabel', () => handleRelabelMapping(mapping)],
+                                                    ['UAssetGUI', () => handleInstallMapping(mapping)],
+                                                    ['Delete', () => handleDeleteMapping(mapping)]
+                                                ].map(([label, onClick]) => (
+                                                    <button
+                                                        key={label}
+                                                        onClick={onClick}
+                                                        className="px-2 py-0.5 font-medium rounded-none"
+                                                        style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                                    >
+                                                        {label}
+                                                    </button>
+                                                ))}
+                                            </div>
+                                        ))}
+                                    </div>
+                                    <div className="flex gap-2">
+                                        <input
+                                            type="text"
+                                            value={mappingLabelDraft}
+                                            onChange={(e) => setMappingLabelDraft(e.target.value)}
+                                            placeholder="Game patch, e.g. S5.5"
+                                            className="flex-grow px-3 py-1 rounded-none text-sm"
+                                            style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}
+                                        />
+                                        <button
+                                            onClick={handleImportMapping}
+                                            className="px-3 py-1 text-sm font-medium rounded-none"
+                                            style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                        >
+                                            Import
+                                        </button>
+                                    </div>
+                                    <p className="text-xs mt-1 opacity-50 italic" style={{ color: 'var(--text-3)' }}>
+                                        Imported mappings are kept per game patch; importing the same file twice keeps one copy. UAssetGUI copies it to UAssetGUI's Rivals.usmap for the batch scripts.
+                                    </p>
+                                </div>
+
+                                {/* Conversion Tool */}
+                                <div>
+                                    <label className="block text-sm font-medium mb-1" style={{ color: 'var(--text-2)' }}>
+                                        Conversion Tool (UAssetCLI)
+                                    </label>
+                                    <div className="flex gap-2">
+                                        <input
+                                            type="text"
+                                            value={settings.uassetCliPath || ''}
+                                            readOnly
+                                            placeholder="Auto-detect"
+                                            className="flex-grow px-3 py-2 rounded-none text-sm"
+                                            style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-3)' }}
+                                        />
+                                        <button
+                                            onClick={async () => {
+                                                const path = await openDialog({
+                                                    filters: [{ name: 'UAssetCLI', extensions: ['exe', 'dll', '*'] }],
+                                                    multiple: false,
+                                                    title: 'Select UAssetCLI executable or UAssetCLI.dll'
+                                                });
+                                                if (path) {
+                                                    try {
+                                                        await invoke('set_uasset_cli_path', { path });
+                                                        setSettings(prev => ({ ...prev, uassetCliPath: path }));
+                                                        setToolCheck(null);
+                                                    } catch (err) {
+                                                        alert(describeError(err));
+                                                    }
                                                 }
                                             }}
                                             className="px-4 py-2 font-medium rounded-none"
@@ -2412,6 +2966,103 @@ function App() {
                                             Browse
                                         </button>
                                     </div>
+                                    <div className="flex items-center justify-between mt-2 gap-2">
+                                        <span className="text-xs break-all" style={{ color: toolCheck?.error ? 'var(--accent-warning, #f59e0b)' : 'var(--text-4)' }}>
+                                            {toolCheck
+                                                ? (toolCheck.error || `${toolCheck.runtime}${toolCheck.runtime_version ? ` ${toolCheck.runtime_version}` : ''} · UAssetAPI ${toolCheck.tool_version || 'unknown'} · ${toolCheck.path}`)
+                                                : ''}
+                                        </span>
+                                        <div className="flex gap-2 shrink-0">
+                                            {settings.uassetCliPath && (
+                                                <button
+                                                    onClick={async () => {
+                                                        await invoke('set_uasset_cli_path', { path: null });
+                                                        setSettings(prev => ({ ...prev, uassetCliPath: null }));
+                                                        setToolCheck(null);
+                                                    }}
+                                                    className="px-3 py-1 text-sm font-medium rounded-none"
+                                                    style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                                >
+                                                    Reset
+                                                </button>
+                                            )}
+                                            <button
+                                                onClick={async () => {
+                                                    try {
+                                                        setToolCheck(await invoke('check_conversion_tool'));
+                                                    } catch (err) {
+                                                        setToolCheck({ error: describeError(err) });
+                                                    }
+                                                }}
+                                                className="px-3 py-1 text-sm font-medium rounded-none"
+                                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            >
+                                                Check
+                                            </button>
+                                        </div>
+                                    </div>
+                                </div>
+
+                                {/* Conversion Concurrency */}
+                                <div>
+                                    <label className="block text-sm font-medium mb-1" style={{ color: 'var(--text-2)' }}>
+                                        Parallel Conversion Processes
+                                    </label>
+                                    <div className="flex items-center gap-3">
+                                        <input
+                                            type="number"
+                                            min={1}
+                                            max={16}
+                                            value={settings.conversionConcurrency || 1}
+                                            onChange={async (e) => {
+                                                const concurrency = Math.min(16, Math.max(1, parseInt(e.target.value, 10) || 1));
+                                                setSettings(prev => ({ ...prev, conversionConcurrency: concurrency }));
+                                                try {
+                                                    await invoke('set_conversion_concurrency', { concurrency });
+                                                } catch (err) {
+                                                    addDebugLog(`Failed to save concurrency: ${describeError(err)}`);
+                                                }
+                                            }}
+                                            className="w-20 px-3 py-2 rounded-none text-sm"
+                                            style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}
+                                        />
+                                        <span className="text-xs" style={{ color: 'var(--text-4)' }}>
+                                            Large batches are split across this many UAssetCLI processes. Each one loads its own copy of the .usmap.
+                                        </span>
+                                    </div>
+                                </div>
+
+                                {/* Engine Version */}
+                                <div>
+                                    <label className="block text-sm font-medium mb-1" style={{ color: 'var(--text-2)' }}>
+                                        Engine Version
+                                    </label>
+                                    <div className="flex items-center gap-3">
+                                        <select
+                                            value={settings.engineVersion}
+                                            onChange={async (e) => {
+                                                const version = e.target.value;
+                                                const previous = settings.engineVersion;
+                                                setSettings(prev => ({ ...prev, engineVersion: version }));
+                                                try {
+                                                    await invoke('set_engine_version', { version });
+                                                    addDebugLog(`Engine version set to ${version}`);
+                                                } catch (err) {
+                                                    setSettings(prev => ({ ...prev, engineVersion: previous }));
+                                                    addDebugLog(`Failed to set engine version: ${describeError(err)}`);
+                                                }
+                                            }}
+                                            className="px-3 py-2 rounded-none text-sm"
+                                            style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}
+                                        >
+                                            {(engineVersions.includes(settings.engineVersion) ? engineVersions : [settings.engineVersion, ...engineVersions]).map(v => (
+                                                <option key={v} value={v}>{v}</option>
+                                            ))}
+                                        </select>
+                                        <span className="text-xs" style={{ color: 'var(--text-4)' }}>
+                                            Unreal version .uasset files are read as. Marvel Rivals uses VER_UE5_3.
+                                        </span>
+                                    </div>
                                 </div>
 
                                 {/* Cache Info */}
@@ -2421,15 +3072,111 @@ function App() {
                                     </label>
                                     <div className="flex items-center justify-between p-3 rounded" style={{ backgroundColor: 'var(--bg-2)' }}>
                                         <span className="text-sm" style={{ color: 'var(--text-3)' }}>
-                                            {cacheInfo.fileCount} files ({(cacheInfo.totalSizeBytes / 1024 / 1024).toFixed(1)} MB)
+                                            {cacheInfo.fileCount} files ({(cacheInfo.totalSizeBytes / 1024 / 1024).toFixed(1)} MB, {(cacheInfo.evictableBytes / 1024 / 1024).toFixed(1)} MB evictable)
                                         </span>
+                                        <div className="flex gap-2">
+                                            <button
+                                                onClick={async () => {
+                                                    try {
+                                                        const report = await invoke('repair_cache');
+                                                        const freedMb = (report.freed_bytes / 1024 / 1024).toFixed(1);
+                                                        addDebugLog(`Cache repair: ${report.missing_json.length} missing, ${report.unreadable.length} unreadable, ${report.rebuilt.length} rebuilt, ${report.orphans_removed.length} orphans removed, ${freedMb} MB freed`);
+                                                        await handleOpenSettings();
+                                                    } catch (err) {
+                                                        alert(`Failed to repair cache: ${describeError(err)}`);
+                                                    }
+                                                }}
+                                                className="px-3 py-1 text-sm font-medium rounded-none"
+                                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            >
+                                                Repair
+                                            </button>
+                                            <button
+                                                onClick={handleExportCacheBundle}
+                                                className="px-3 py-1 text-sm font-medium rounded-none"
+                                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            >
+                                                Export
+                                            </button>
+                                            <button
+                                                onClick={handleImportCacheBundle}
+                                                className="px-3 py-1 text-sm font-medium rounded-none"
+                                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            >
+                                                Import
+                                            </button>
+                                            <button
+                                                onClick={() => setShowClearCacheConfirm(true)}
+                                                className="px-3 py-1 text-sm font-medium rounded-none"
+                                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            >
+                                                Clear Cache
+                                            </button>
+                                        </div>
+                                    </div>
+                                    <div className="flex gap-2 mt-2">
+                                        <input
+                                            type="text"
+                                            value={cacheInfo.cacheDir || ''}
+                                            readOnly
+                                            className="flex-grow px-3 py-2 rounded-none text-sm"
+                                            style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-3)' }}
+                                        />
                                         <button
-                                            onClick={() => setShowClearCacheConfirm(true)}
-                                            className="px-3 py-1 text-sm font-medium rounded-none"
-                                            style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            disabled={isMovingCache}
+                                            onClick={async () => {
+                                                const location = await openDialog({
+                                                    directory: true,
+                                                    multiple: false,
+                                                    title: 'Select a folder for the conversion cache'
+                                                });
+                                                if (location) await handleMoveCache(location);
+                                            }}
+                                            className="px-4 py-2 font-medium rounded-none"
+                                            style={{ backgroundColor: 'var(--accent-main)', color: 'var(--bg-4)' }}
                                         >
-                                            Clear Cache
+                                            {isMovingCache ? 'Moving...' : 'Move'}
                                         </button>
+                                        {settings.cacheLocation && (
+                                            <button
+                                                disabled={isMovingCache}
+                                                onClick={() => handleMoveCache(null)}
+                                                className="px-3 py-1 text-sm font-medium rounded-none"
+                                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                            >
+                                                Reset
+                                            </button>
+                                        )}
+                                    </div>
+                                </div>
+
+                                {/* Cache Size Limit */}
+                                <div>
+                                    <label className="block text-sm font-medium mb-1" style={{ color: 'var(--text-2)' }}>
+                                        Cache Size Limit (GB)
+                                    </label>
+                                    <div className="flex items-center gap-3">
+                                        <input
+                                            type="number"
+                                            min={0}
+                                            step={1}
+                                            value={settings.maxCacheBytes ? settings.maxCacheBytes / 1024 ** 3 : 0}
+                                            onChange={async (e) => {
+                                                const gb = Math.max(0, parseFloat(e.target.value) || 0);
+                                                const maxBytes = gb > 0 ? Math.round(gb * 1024 ** 3) : null;
+                                                setSettings(prev => ({ ...prev, maxCacheBytes: maxBytes }));
+                                                try {
+                                                    await invoke('set_max_cache_bytes', { maxBytes });
+                                                } catch (err) {
+                                                    addDebugLog(`Failed to save cache limit: ${describeError(err)}`);
+                                                }
+                                            }}
+                                            className="w-20 px-3 py-2 rounded-none text-sm"
+                                            style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}
+                                        />
+                                        <span className="text-xs" style={{ color: 'var(--text-4)' }}>
+                                            After each conversion the least recently used JSON files are deleted until the cache fits. 0 means no limit.
+                                        </span>
                                     </div>
                                 </div>
 
@@ -2442,6 +3189,53 @@ function App() {
                                         setSettings(prev => ({ ...prev, autoClearCache: val }));
                                     }}
                                 />
+
+                                {/* Round-trip Verification Toggle */}
+                                <ToggleSwitch
+                                    label="Verify Saved Assets (slower)"
+                                    enabled={settings.verifyRoundTrip}
+                                    setEnabled={async (val) => {
+                                        await invoke('set_verify_round_trip', { enabled: val });
+                                        setSettings(prev => ({ ...prev, verifyRoundTrip: val }));
+                                    }}
+                                />
+
+                                {/* Project Settings */}
+                                <div>
+                                    <label className="block text-sm font-medium mb-1" style={{ color: 'var(--text-2)' }}>
+                                        Project Settings
+                                    </label>
+                                    {settingLayers.projectFile ? (
+                                        <>
+                                            <input
+                                                type="text"
+                                                value={settingLayers.projectFile}
+                                                readOnly
+                                                className="w-full px-3 py-2 mb-2 rounded-none text-sm"
+                                                style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-3)' }}
+                                            />
+                                            <div className="grid grid-cols-2 gap-1">
+                                                {Object.entries(PROJECT_SETTING_LABELS).map(([key, label]) => (
+                                                    <label key={key} className="flex items-center gap-2 text-xs" style={{ color: 'var(--text-2)' }}>
+                                                        <input
+                                                            type="checkbox"
+                                                            checked={settingLayers.sources[key] === 'project'}
+                                                            onChange={(e) => handleProjectOverrideToggle(key, e.target.checked)}
+                                                        />
+                                                        {label}
+                                                    </label>
+                                                ))}
+                                            </div>
+                                            <span className="text-xs" style={{ color: 'var(--text-4)' }}>
+                                                Checked settings are kept in this folder's .rvfxe.json and changes to them are saved there; the rest come from your global settings.
+                                            </span>
+                                        </>
+                                    ) : (
+                                        <span className="text-xs" style={{ color: 'var(--text-4)' }}>
+                                            Select a .uasset folder to override settings for it with a .rvfxe.json.
+                                        </span>
+                                    )}
+                                </div>
                             </div>
 
                             <div className="mt-8 text-center">
@@ -2477,6 +3271,76 @@ function App() {
                                 Incorrectly modifying these keywords may result in missing color parameters or unintended inclusions. Proceed with caution.
                             </div>
 
+                            <div className="mb-4 space-y-2">
+                                <div className="flex gap-2 items-center">
+                                    <span className="text-sm font-medium" style={{ color: 'var(--text-2)' }}>Profile</span>
+                                    <select
+                                        value={filterProfiles.active}
+                                        onChange={(e) => runFilterProfileCommand('switch_filter_profile', { name: e.target.value })}
+                                        className="flex-grow px-3 py-2 rounded-none text-sm"
+                                        style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}
+                                    >
+                                        {Object.keys(filterProfiles.profiles).map(name => (
+                                            <option key={name} value={name}>{name}</option>
+                                        ))}
+                                    </select>
+                                    <button
+                                        onClick={() => handleImportFilterProfile(false)}
+                                        className="px-3 py-1 text-sm font-medium rounded-none"
+                                        style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                    >
+                                        Import
+                                    </button>
+                                    <button
+                                        onClick={() => handleImportFilterProfile(true)}
+                                        className="px-3 py-1 text-sm font-medium rounded-none"
+                                        style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                    >
+                                        Link
+                                    </button>
+                                    <button
+                                        onClick={handleExportFilterProfile}
+                                        className="px-3 py-1 text-sm font-medium rounded-none"
+                                        style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                    >
+                                        Export
+                                    </button>
+                                </div>
+                                <div className="flex gap-2">
+                                    <input
+                                        type="text"
+                                        value={profileNameDraft}
+                                        onChange={(e) => setProfileNameDraft(e.target.value)}
+                                        placeholder="Profile name"
+                                        className="flex-grow px-3 py-1 rounded-none text-sm"
+                                        style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-2)' }}
+                                    />
+                                    {[
+                                        ['New', handleCreateFilterProfile],
+                                        ['Duplicate', handleDuplicateFilterProfile],
+                                        ['Rename', handleRenameFilterProfile],
+                                        ['Delete', handleDeleteFilterProfile]
+                                    ].map(([label, onClick]) => (
+                                        <button
+                                            key={label}
+                                            onClick={onClick}
+                                            className="px-3 py-1 text-sm font-medium rounded-none"
+                                            style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                                        >
+                                            {label}
+                                        </button>
+                                    ))}
+                                </div>
+                                {filterProfiles.links?.[filterProfiles.active] && (
+                                    <p className="text-xs" style={{ color: 'var(--text-3)' }}>
+                                        Linked to {filterProfiles.links[filterProfiles.active]}: edits are saved to it and its changes are picked up automatically.
+                                    </p>
+                                )}
+                                <p className="text-xs opacity-50 italic" style={{ color: 'var(--text-3)' }}>
+                                    Exported projects remember the active profile. Changes apply to assets loaded afterwards.
+                                </p>
+                            </div>
+
                             <div className="flex-grow space-y-4 overflow-y-auto pr-1">
                                 <div>
                                     <KeywordListEditor
@@ -2501,6 +3365,24 @@ function App() {
                                     })}
                                 />
 
+                                <FilterRulesEditor
+                                    rules={filterDictionary.rules || []}
+                                    onChange={(rules) => handleFilterDictionaryChange({ ...filterDictionary, rules })}
+                                />
+
+                                <div className="flex gap-2 items-center -mt-2 mb-4 text-xs" style={{ color: 'var(--text-2)' }}>
+                                    <span>When an allow and a deny rule tie on priority</span>
+                                    <select
+                                        value={filterDictionary.precedence || 'deny_over_allow'}
+                                        onChange={(e) => handleFilterDictionaryChange({ ...filterDictionary, precedence: e.target.value })}
+                                        className="px-1 py-1 rounded-none"
+                                        style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-1)' }}
+                                    >
+                                        <option value="deny_over_allow">deny wins</option>
+                                        <option value="allow_over_deny">allow wins</option>
+                                    </select>
+                                </div>
+
                                 <KeywordListEditor
                                     title="Color Property Names"
                                     keywords={filterDictionary.color_property_names || []}
@@ -2509,6 +3391,39 @@ function App() {
                                         color_property_names: newKeywords
                                     })}
                                 />
+
+                                <div className="mb-4">
+                                    <label className="block text-xs font-bold uppercase mb-1 opacity-70" style={{ color: 'var(--text-2)' }}>
+                                        Test Parameter Names
+                                    </label>
+                                    <textarea
+                                        value={filterTestNames}
+                                        onChange={(e) => setFilterTestNames(e.target.value)}
+                                        placeholder="One name per line"
+                                        rows={3}
+                                        className="w-full text-xs px-2 py-1 rounded-none focus:outline-none font-mono"
+                                        style={{ backgroundColor: 'var(--bg-2)', color: 'var(--text-1)' }}
+                                    />
+                                    <button
+                                        onClick={handleTestFilter}
+                                        className="px-2 py-1 text-xs font-bold rounded-none"
+                                        style={{ backgroundColor: 'var(--accent-main)', color: 'var(--bg-4)' }}
+                                    >
+                                        Test
+                                    </button>
+                                    {filterTestResults?.error && (
+                                        <div className="text-xs mt-2 text-red-500">{filterTestResults.error}</div>
+                                    )}
+                                    {Array.isArray(filterTestResults) && (
+                                        <div className="mt-2 space-y-1 text-xs font-mono">
+                                            {filterTestResults.map((decision, idx) => (
+                                                <div key={idx} style={{ color: decision.matched ? 'var(--text-1)' : 'var(--text-4)' }}>
+                                                    {decision.matched ? '✓' : '✗'} {decision.name} <span className="opacity-60">— {describeFilterRule(decision.rule)}</span>
+                                                </div>
+                                            ))}
+                                        </div>
+                                    )}
+                                </div>
                             </div>
 
                             <div className="text-center pt-2 border-t" style={{ borderColor: 'var(--bg-1)' }}>
@@ -2545,7 +3460,7 @@ function App() {
                                 <button
                                     onClick={async () => {
                                         await invoke('clear_cache');
-                                        setCacheInfo({ fileCount: 0, totalSizeBytes: 0 });
+                                        setCacheInfo(prev => ({ ...prev, fileCount: 0, totalSizeBytes: 0, evictableBytes: 0 }));
                                         addDebugLog('Cache cleared');
                                         setShowClearCacheConfirm(false);
                                     }}
@@ -2593,6 +3508,14 @@ function App() {
                                     </p>
                                 </>
                             )}
+                            <button
+                                onClick={handleCancelConversion}
+                                disabled={isCancelling}
+                                className="mt-6 px-6 py-2 font-medium rounded-none transition-colors disabled:opacity-50"
+                                style={{ backgroundColor: 'var(--bg-1)', color: 'var(--text-2)' }}
+                            >
+                                {isCancelling ? 'Cancelling...' : 'Cancel'}
+                            </button>
                         </div>
                     </div>
                 )
@@ -21,7 +21,15 @@ class Program
             return 1;
         }
 
-        var command = args[0].ToLowerInvariant();
+        if (args[0].ToLowerInvariant() == "serve")
+            return HandleServe();
+
+        return Dispatch(args);
+    }
+
+    static int Dispatch(string[] args)
+    {
+        var command = args.Length > 0 ? args[0].ToLowerInvariant() : "";
 
         try
         {
@@ -32,6 +40,7 @@ class Program
                 "batch-to-json" => HandleBatchToJson(args),
                 "batch-from-json" => HandleBatchFromJson(args),
                 "version" => HandleVersion(),
+                "engine-versions" => HandleEngineVersions(),
                 "--help" or "-h" or "help" => PrintUsage(),
                 _ => PrintUsage()
             };
@@ -43,6 +52,74 @@ class Program
         }
     }
 
+    class WorkerRequest
+    {
+        public long Id { get; set; }
+        public string[] Args { get; set; } = Array.Empty<string>();
+    }
+
+    // Long-lived mode: one JSON request per stdin line, e.g.
+    // {"id":1,"args":["to-json","a.uasset","a.json","--usmap","x.usmap"]}.
+    // Each request gets one stdout line holding exactly what the one-shot
+    // command would have printed. Progress keeps going to stderr, followed by
+    // a "done" marker so the caller knows the request's progress is complete.
+    static int HandleServe()
+    {
+        var stdout = Console.Out;
+        stdout.WriteLine(JsonSerializer.Serialize(new { type = "ready" }, JsonOptions));
+        stdout.Flush();
+
+        string? line;
+        while ((line = Console.In.ReadLine()) != null)
+        {
+            if (string.IsNullOrWhiteSpace(line))
+                continue;
+
+            WorkerRequest? request = null;
+            try
+            {
+                request = JsonSerializer.Deserialize<WorkerRequest>(line, JsonOptions);
+            }
+            catch (JsonException)
+            {
+            }
+
+            var captured = new StringWriter();
+            int exitCode;
+            Console.SetOut(captured);
+            try
+            {
+                if (request == null)
+                {
+                    OutputError(1, $"Invalid worker request: {line}");
+                    exitCode = 1;
+                }
+                else
+                {
+                    exitCode = Dispatch(request.Args);
+                }
+            }
+            finally
+            {
+                Console.SetOut(stdout);
+            }
+
+            var id = request?.Id ?? 0;
+            Console.Error.WriteLine(JsonSerializer.Serialize(new { type = "done", id }, JsonOptions));
+            Console.Error.Flush();
+            stdout.WriteLine(JsonSerializer.Serialize(new
+            {
+                type = "response",
+                id,
+                exitCode,
+                output = captured.ToString()
+            }, JsonOptions));
+            stdout.Flush();
+        }
+
+        return 0;
+    }
+
     static int PrintUsage()
     {
         Console.WriteLine(@"
@@ -52,13 +129,13 @@ Usage:
   UAssetCLI <command> [options]
 
 Commands:
-  to-json <input.uasset> <output.json> [--usmap <path>]
+  to-json <input.uasset> <output.json> [--usmap <path>] [--engine <ver>]
       Convert a .uasset file to JSON
 
   from-json <input.json> <output.uasset> [--usmap <path>]
       Convert a JSON file back to .uasset
 
-  batch-to-json <input-list.txt> <output-dir> [--usmap <path>]
+  batch-to-json <input-list.txt> <output-dir> [--usmap <path>] [--engine <ver>]
       Convert multiple .uasset files to JSON (for 100+ files)
       Input list: one .uasset path per line (or: inputPath,outputRelPath)
 
@@ -69,8 +146,15 @@ Commands:
   version
       Display version information
 
+  engine-versions
+      List the values accepted by --engine
+
+  serve
+      Run as a worker: read JSON requests from stdin, one per line
+
 Options:
   --usmap, -u <path>    Path to .usmap mapping file (required for Marvel Rivals)
+  --engine, -e <ver>    Engine version used to read .uasset files (default: VER_UE5_3)
   --help, -h            Show this help message
 ");
         return 1;
@@ -88,25 +172,73 @@ Options:
         return null;
     }
 
+    // Marvel Rivals
+    const EngineVersion DefaultEngineVersion = EngineVersion.VER_UE5_3;
+
+    static IEnumerable<string> EngineVersionNames() =>
+        Enum.GetNames<EngineVersion>()
+            .Where(name => name != nameof(EngineVersion.UNKNOWN) && !name.Contains("AUTOMATIC"));
+
+    // Reads --engine, reporting unknown values the same way as other usage errors
+    static bool TryGetEngineVersion(string[] args, out EngineVersion engineVersion)
+    {
+        engineVersion = DefaultEngineVersion;
+        var value = GetOptionValue(args, "--engine", "-e");
+        if (value == null)
+            return true;
+
+        if (EngineVersionNames().Contains(value, StringComparer.OrdinalIgnoreCase) &&
+            Enum.TryParse(value, true, out engineVersion))
+            return true;
+
+        OutputError(1, $"Unknown engine version: {value}. Run 'UAssetCLI engine-versions' for the list.");
+        return false;
+    }
+
+    // Parsed mappings are reused across requests in serve mode
+    static readonly Dictionary<string, (DateTime Modified, Usmap Usmap)> UsmapCache = new();
+
     static Usmap? LoadUsmap(string? usmapPath)
     {
         if (string.IsNullOrEmpty(usmapPath) || !File.Exists(usmapPath))
             return null;
 
-        return new Usmap(usmapPath);
+        var modified = File.GetLastWriteTimeUtc(usmapPath);
+        lock (UsmapCache)
+        {
+            if (UsmapCache.TryGetValue(usmapPath, out var cached) && cached.Modified == modified)
+                return cached.Usmap;
+
+            Usmap usmap;
+            try
+            {
+                usmap = new Usmap(usmapPath);
+            }
+            catch (Exception ex)
+            {
+                throw new UsmapLoadException($"Failed to load usmap {usmapPath}: {ex.Message}", ex);
+            }
+            UsmapCache[usmapPath] = (modified, usmap);
+            return usmap;
+        }
     }
 
+    // Reported with exit code 3 so callers can tell bad mappings from bad assets
+    class UsmapLoadException(string message, Exception inner) : Exception(message, inner);
+
     static int HandleToJson(string[] args)
     {
         if (args.Length < 3)
         {
-            OutputError(1, "Usage: UAssetCLI to-json <input.uasset> <output.json> [--usmap <path>]");
+            OutputError(1, "Usage: UAssetCLI to-json <input.uasset> <output.json> [--usmap <path>] [--engine <ver>]");
             return 1;
         }
 
         var inputPath = args[1];
         var outputPath = args[2];
         var usmapPath = GetOptionValue(args, "--usmap", "-u");
+        if (!TryGetEngineVersion(args, out var engineVersion))
+            return 1;
 
         if (!File.Exists(inputPath))
         {
@@ -117,7 +249,7 @@ Options:
         try
         {
             var usmap = LoadUsmap(usmapPath);
-            var asset = new UAsset(inputPath, EngineVersion.VER_UE5_3, usmap);
+            var asset = new UAsset(inputPath, engineVersion, usmap);
 
             string json = asset.SerializeJson(Newtonsoft.Json.Formatting.Indented);
 
@@ -136,6 +268,16 @@ Options:
             });
             return 0;
         }
+        catch (UsmapLoadException ex)
+        {
+            OutputError(3, ex.Message, ex.InnerException?.ToString());
+            return 3;
+        }
+        catch (IOException ex)
+        {
+            OutputError(5, $"Write error: {ex.Message}", ex.ToString());
+            return 5;
+        }
         catch (Exception ex)
         {
             OutputError(4, $"Parse error: {ex.Message}", ex.ToString());
@@ -182,24 +324,36 @@ Options:
             });
             return 0;
         }
-        catch (Exception ex)
+        catch (UsmapLoadException ex)
+        {
+            OutputError(3, ex.Message, ex.InnerException?.ToString());
+            return 3;
+        }
+        catch (IOException ex)
         {
             OutputError(5, $"Write error: {ex.Message}", ex.ToString());
             return 5;
         }
+        catch (Exception ex)
+        {
+            OutputError(4, $"Parse error: {ex.Message}", ex.ToString());
+            return 4;
+        }
     }
 
     static int HandleBatchToJson(string[] args)
     {
         if (args.Length < 3)
         {
-            OutputError(1, "Usage: UAssetCLI batch-to-json <input-list.txt> <output-dir> [--usmap <path>]");
+            OutputError(1, "Usage: UAssetCLI batch-to-json <input-list.txt> <output-dir> [--usmap <path>] [--engine <ver>]");
             return 1;
         }
 
         var inputListPath = args[1];
         var outputDir = args[2];
         var usmapPath = GetOptionValue(args, "--usmap", "-u");
+        if (!TryGetEngineVersion(args, out var engineVersion))
+            return 1;
 
         if (!File.Exists(inputListPath))
         {
@@ -242,10 +396,10 @@ Options:
                         lock (lockObj)
                         {
                             failed++;
-                            results.Add(new { success = false, fileName, error = "File not found" });
+                            results.Add(new { success = false, fileName, uassetPath = inputPath, error = "File not found" });
                         }
                         var current = Interlocked.Increment(ref completed);
-                        OutputProgress(current, total, fileName, false, "File not found");
+                        OutputProgress(current, total, fileName, false, "File not found", inputPath);
                         return;
                     }
 
@@ -254,7 +408,7 @@ Options:
                     if (!string.IsNullOrEmpty(fileOutputDir))
                          Directory.CreateDirectory(fileOutputDir);
 
-                    var asset = new UAsset(inputPath, EngineVersion.VER_UE5_3, usmap);
+                    var asset = new UAsset(inputPath, engineVersion, usmap);
                     string json = asset.SerializeJson(Newtonsoft.Json.Formatting.Indented);
                     File.WriteAllText(outputPath, json);
 
@@ -271,17 +425,17 @@ Options:
                         });
                     }
                     var currentSuccess = Interlocked.Increment(ref completed);
-                    OutputProgress(currentSuccess, total, fileName, false, null);
+                    OutputProgress(currentSuccess, total, fileName, false, null, inputPath);
                 }
                 catch (Exception ex)
                 {
                     lock (lockObj)
                     {
                         failed++;
-                        results.Add(new { success = false, fileName, error = ex.Message });
+                        results.Add(new { success = false, fileName, uassetPath = inputPath, error = ex.Message });
                     }
                     var currentFail = Interlocked.Increment(ref completed);
-                    OutputProgress(currentFail, total, fileName, false, ex.Message);
+                    OutputProgress(currentFail, total, fileName, false, ex.Message, inputPath);
                 }
             });
 
@@ -299,6 +453,11 @@ Options:
 
             return failed > 0 ? 4 : 0;
         }
+        catch (UsmapLoadException ex)
+        {
+            OutputError(3, ex.Message, ex.InnerException?.ToString());
+            return 3;
+        }
         catch (Exception ex)
         {
             OutputError(4, $"Batch processing error: {ex.Message}", ex.ToString());
@@ -358,10 +517,10 @@ Options:
                         lock (lockObj)
                         {
                             failed++;
-                            results.Add(new { success = false, fileName, error = "File not found" });
+                            results.Add(new { success = false, fileName, jsonPath, uassetPath = outputPath, error = "File not found" });
                         }
                         var current = Interlocked.Increment(ref completed);
-                        OutputProgress(current, total, fileName, false, "File not found");
+                        OutputProgress(current, total, fileName, false, "File not found", jsonPath);
                         return;
                     }
 
@@ -388,17 +547,17 @@ Options:
                         });
                     }
                     var currentSuccess = Interlocked.Increment(ref completed);
-                    OutputProgress(currentSuccess, total, fileName, false, null);
+                    OutputProgress(currentSuccess, total, fileName, false, null, jsonPath);
                 }
                 catch (Exception ex)
                 {
                     lock (lockObj)
                     {
                         failed++;
-                        results.Add(new { success = false, fileName, error = ex.Message });
+                        results.Add(new { success = false, fileName, jsonPath, uassetPath = outputPath, error = ex.Message });
                     }
                     var currentFail = Interlocked.Increment(ref completed);
-                    OutputProgress(currentFail, total, fileName, false, ex.Message);
+                    OutputProgress(currentFail, total, fileName, false, ex.Message, jsonPath);
                 }
             });
 
@@ -414,6 +573,11 @@ Options:
 
             return failed > 0 ? 5 : 0;
         }
+        catch (UsmapLoadException ex)
+        {
+            OutputError(3, ex.Message, ex.InnerException?.ToString());
+            return 3;
+        }
         catch (Exception ex)
         {
             OutputError(5, $"Batch processing error: {ex.Message}", ex.ToString());
@@ -424,17 +588,30 @@ Options:
     static int HandleVersion()
     {
         var version = typeof(UAsset).Assembly.GetName().Version;
+        var cliVersion = typeof(Program).Assembly.GetName().Version;
         Console.WriteLine(JsonSerializer.Serialize(new
         {
             success = true,
             tool = "UAssetCLI",
+            cliVersion = cliVersion?.ToString() ?? "unknown",
             uassetApiVersion = version?.ToString() ?? "unknown",
             variant = "Lightweight"
         }, JsonOptions));
         return 0;
     }
 
-    static void OutputProgress(int current, int total, string fileName, bool cached, string? error)
+    static int HandleEngineVersions()
+    {
+        Console.WriteLine(JsonSerializer.Serialize(new
+        {
+            success = true,
+            @default = DefaultEngineVersion.ToString(),
+            versions = EngineVersionNames().ToList()
+        }, JsonOptions));
+        return 0;
+    }
+
+    static void OutputProgress(int current, int total, string fileName, bool cached, string? error, string? inputPath = null)
     {
         // JSON-line format for streaming progress (to stderr so it doesn't mix with final output)
         Console.Error.WriteLine(JsonSerializer.Serialize(new
@@ -444,7 +621,8 @@ Options:
             total,
             fileName,
             cached,
-            error
+            error,
+            inputPath
         }, JsonOptions));
     }
 
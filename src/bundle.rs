@@ -0,0 +1,393 @@
+//! Shareable cache bundles.
+//!
+//! A bundle is one zstd stream: a JSON header line, then for every entry a
+//! JSON record line followed by `size` bytes of converted JSON. Records are
+//! keyed by the asset's content hash and the cache key, never by path, so a
+//! bundle gives cache hits on any copy of the same game files.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, ErrorCode};
+use crate::{CacheIndex, CacheKey, ImportedEntry};
+
+const BUNDLE_FORMAT: &str = "rvfxe-cache-bundle";
+const BUNDLE_VERSION: u32 = 1;
+
+/// Where imported JSON lives inside the cache directory.
+pub const IMPORTED_DIR: &str = "imported";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleHeader {
+    format: String,
+    version: u32,
+    entry_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleRecord {
+    asset_hash: String,
+    key: CacheKey,
+    /// Name of the source asset, only used to name the imported file.
+    file_name: String,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CacheBundleReport {
+    pub bundle_path: String,
+    /// Entries written to or taken from the bundle.
+    pub entries: usize,
+    /// Import only: entries this cache already had.
+    pub skipped: usize,
+    pub bytes: u64,
+}
+
+/// Writes every complete entry of `index` to `bundle_path`. Entries for the
+/// same content and key are written once.
+pub fn export(index: &CacheIndex, bundle_path: &Path) -> Result<CacheBundleReport, AppError> {
+    let mut seen = HashSet::new();
+    let mut sources = Vec::new();
+    for entry in index.entries.values() {
+        let name = Path::new(&entry.uasset_path)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        sources.push((&entry.hash, &entry.key, name, &entry.json_path));
+    }
+    for (hash, imported) in &index.imported {
+        for entry in imported {
+            sources.push((hash, &entry.key, entry.file_name.clone(), &entry.json_path));
+        }
+    }
+    sources.retain(|(hash, key, _, json_path)| {
+        **key != CacheKey::default()
+            && Path::new(json_path).is_file()
+            && seen.insert(((*hash).clone(), (*key).clone()))
+    });
+
+    let io_err = |e: io::Error| AppError::io(bundle_path, e);
+    let file = File::create(bundle_path).map_err(io_err)?;
+    let mut out = zstd::stream::write::Encoder::new(BufWriter::new(file), 0).map_err(io_err)?;
+    write_line(
+        &mut out,
+        &BundleHeader {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            entry_count: sources.len(),
+        },
+    )
+    .map_err(io_err)?;
+
+    let mut report = CacheBundleReport {
+        bundle_path: bundle_path.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    for (hash, key, file_name, json_path) in sources {
+        let mut json = File::open(json_path).map_err(|e| AppError::io(json_path, e))?;
+        let size = json
+            .metadata()
+            .map_err(|e| AppError::io(json_path, e))?
+            .len();
+        let record = BundleRecord {
+            asset_hash: hash.clone(),
+            key: key.clone(),
+            file_name,
+            size,
+        };
+        write_line(&mut out, &record).map_err(io_err)?;
+        let copied = io::copy(&mut (&mut json).take(size), &mut out).map_err(io_err)?;
+        if copied != size {
+            // Shrunk while exporting: the record promised more bytes than exist
+            return Err(AppError::io(
+                json_path,
+                io::Error::new(io::ErrorKind::UnexpectedEof, "file changed during export"),
+            ));
+        }
+        report.entries += 1;
+        report.bytes += size;
+    }
+    out.finish().and_then(|mut w| w.flush()).map_err(io_err)?;
+    Ok(report)
+}
+
+/// Unpacks `bundle_path` into `cache_dir`, skipping content `index` already
+/// has for the same key. Returns the new entries by asset hash.
+pub fn import(
+    bundle_path: &Path,
+    cache_dir: &Path,
+    index: &CacheIndex,
+) -> Result<(Vec<(String, ImportedEntry)>, CacheBundleReport), AppError> {
+    let invalid =
+        |message: String| AppError::new(ErrorCode::InvalidBundle, message).with_path(bundle_path);
+    let file = File::open(bundle_path).map_err(|e| AppError::io(bundle_path, e))?;
+    let decoder =
+        zstd::stream::read::Decoder::new(file).map_err(|e| AppError::io(bundle_path, e))?;
+    let mut input = BufReader::new(decoder);
+
+    let header: BundleHeader = read_line(&mut input)
+        .map_err(|e| invalid(format!("Not a cache bundle: {}", e)))?
+        .ok_or_else(|| invalid("Not a cache bundle: file is empty".to_string()))?;
+    if header.format != BUNDLE_FORMAT {
+        return Err(invalid("Not a cache bundle".to_string()));
+    }
+    if header.version > BUNDLE_VERSION {
+        return Err(invalid(format!(
+            "Cache bundle version {} is newer than this editor supports",
+            header.version
+        )));
+    }
+
+    let known: HashSet<(&str, &CacheKey)> = index
+        .entries
+        .values()
+        .filter(|entry| Path::new(&entry.json_path).is_file())
+        .map(|entry| (entry.hash.as_str(), &entry.key))
+        .chain(index.imported.iter().flat_map(|(hash, imported)| {
+            imported
+                .iter()
+                .filter(|entry| Path::new(&entry.json_path).is_file())
+                .map(move |entry| (hash.as_str(), &entry.key))
+        }))
+        .collect();
+
+    let imported_dir = cache_dir.join(IMPORTED_DIR);
+    fs::create_dir_all(&imported_dir).map_err(|e| AppError::io(&imported_dir, e))?;
+    let mut report = CacheBundleReport {
+        bundle_path: bundle_path.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let mut imported = Vec::new();
+
+    for _ in 0..header.entry_count {
+        match unpack_record(&mut input, bundle_path, &imported_dir, &known, &mut report) {
+            Ok(Some(entry)) => imported.push(entry),
+            Ok(None) => {}
+            Err(e) => {
+                // All or nothing, so a damaged bundle leaves no orphans behind
+                for (_, entry) in &imported {
+                    let _ = fs::remove_file(&entry.json_path);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    Ok((imported, report))
+}
+
+/// Reads the next record and writes its JSON, unless the cache already has it.
+fn unpack_record(
+    input: &mut impl BufRead,
+    bundle_path: &Path,
+    imported_dir: &Path,
+    known: &HashSet<(&str, &CacheKey)>,
+    report: &mut CacheBundleReport,
+) -> Result<Option<(String, ImportedEntry)>, AppError> {
+    let damaged = |e: io::Error| {
+        AppError::new(
+            ErrorCode::InvalidBundle,
+            format!("Cache bundle is damaged: {}", e),
+        )
+        .with_path(bundle_path)
+    };
+    let cut_off = || {
+        AppError::new(ErrorCode::InvalidBundle, "Cache bundle is cut off").with_path(bundle_path)
+    };
+
+    let record: BundleRecord = read_line(input).map_err(damaged)?.ok_or_else(cut_off)?;
+    // The hash names the imported file, so it must not be able to name a path
+    if record.asset_hash.len() != 64 || !record.asset_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(AppError::new(
+            ErrorCode::InvalidBundle,
+            "Cache bundle has a record with an invalid asset hash",
+        )
+        .with_path(bundle_path));
+    }
+    let mut content = input.by_ref().take(record.size);
+
+    if known.contains(&(record.asset_hash.as_str(), &record.key)) {
+        let size = io::copy(&mut content, &mut io::sink()).map_err(damaged)?;
+        if size != record.size {
+            return Err(cut_off());
+        }
+        report.skipped += 1;
+        return Ok(None);
+    }
+
+    let json_path = imported_dir.join(imported_file_name(&record));
+    let written = File::create(&json_path).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        let size = io::copy(&mut content, &mut out)?;
+        out.flush()?;
+        Ok(size)
+    });
+    match written {
+        Ok(size) if size == record.size => {}
+        Ok(_) => {
+            let _ = fs::remove_file(&json_path);
+            return Err(cut_off());
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&json_path);
+            return Err(AppError::io(&json_path, e));
+        }
+    }
+
+    report.entries += 1;
+    report.bytes += record.size;
+    Ok(Some((
+        record.asset_hash,
+        ImportedEntry {
+            file_name: record.file_name,
+            json_path: json_path.to_string_lossy().to_string(),
+            key: record.key,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+    )))
+}
+
+/// `<asset name>_<hash>_<key digest>.json`, unique per content and key.
+fn imported_file_name(record: &BundleRecord) -> PathBuf {
+    use sha2::{Digest, Sha256};
+    let key = serde_json::to_vec(&record.key).unwrap_or_default();
+    let key_digest = hex::encode(Sha256::digest(&key));
+    let name: String = record
+        .file_name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .collect();
+    PathBuf::from(format!(
+        "{}_{}_{}.json",
+        name,
+        &record.asset_hash[..16],
+        &key_digest[..8]
+    ))
+}
+
+fn write_line<T: Serialize>(out: &mut impl Write, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n")
+}
+
+fn read_line<T: for<'de> Deserialize<'de>>(input: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn key() -> CacheKey {
+        CacheKey {
+            usmap_hash: None,
+            tool_version: Some("1.0.0".to_string()),
+            engine_version: Some("VER_UE5_3".to_string()),
+        }
+    }
+
+    /// Writes a bundle of `records` with the given JSON contents. `extra`
+    /// bytes are claimed by each record but never written.
+    fn write_bundle(path: &Path, records: &[(&str, &str)], extra: u64) {
+        let mut out = zstd::stream::write::Encoder::new(File::create(path).unwrap(), 0).unwrap();
+        let header = BundleHeader {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            entry_count: records.len(),
+        };
+        write_line(&mut out, &header).unwrap();
+        for (hash, json) in records {
+            let record = BundleRecord {
+                asset_hash: hash.to_string(),
+                key: key(),
+                file_name: "MI_Test".to_string(),
+                size: json.len() as u64 + extra,
+            };
+            write_line(&mut out, &record).unwrap();
+            out.write_all(json.as_bytes()).unwrap();
+        }
+        out.finish().unwrap();
+    }
+
+    /// Imports a bundle that must be rejected, and checks that nothing was
+    /// left behind in or next to the cache.
+    fn import_error(records: &[(&str, &str)], extra: u64) -> AppError {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("team.rvfxc");
+        let cache_dir = dir.path().join("cache");
+        write_bundle(&bundle, records, extra);
+        let error = import(&bundle, &cache_dir, &CacheIndex::default()).unwrap_err();
+        let names = |path: &Path| -> Vec<_> {
+            fs::read_dir(path)
+                .unwrap()
+                .map(|e| e.unwrap().file_name())
+                .collect()
+        };
+        assert_eq!(names(dir.path()).len(), 2, "{:?}", names(dir.path()));
+        assert_eq!(names(&cache_dir), [IMPORTED_DIR]);
+        assert!(names(&cache_dir.join(IMPORTED_DIR)).is_empty());
+        error
+    }
+
+    #[test]
+    fn imports_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("team.rvfxc");
+        write_bundle(&bundle, &[(HASH, "{\"a\":1}")], 0);
+
+        let (imported, report) =
+            import(&bundle, &dir.path().join("cache"), &CacheIndex::default()).unwrap();
+        assert_eq!((report.entries, report.skipped, report.bytes), (1, 0, 7));
+        let (hash, entry) = &imported[0];
+        assert_eq!(hash, HASH);
+        assert_eq!(entry.key, key());
+        assert_eq!(fs::read_to_string(&entry.json_path).unwrap(), "{\"a\":1}");
+        assert!(
+            Path::new(&entry.json_path).starts_with(dir.path().join("cache").join(IMPORTED_DIR))
+        );
+    }
+
+    #[test]
+    fn crafted_hashes_are_rejected() {
+        for hash in [
+            "/../../../../../",
+            "../../../../../../../../../../../../../../../../../../../../../escape",
+            // Byte 16 falls inside a multibyte character
+            "0123456789abcde\u{e9}0123456789abcdef0123456789abcdef0123456789abc",
+            "",
+        ] {
+            let error = import_error(&[(HASH, "{}"), (hash, "{}")], 0);
+            assert_eq!(error.code, ErrorCode::InvalidBundle, "{:?}", hash);
+        }
+    }
+
+    #[test]
+    fn truncated_bundles_are_rejected() {
+        let error = import_error(&[(HASH, "{\"a\":1}")], 5);
+        assert_eq!(error.code, ErrorCode::InvalidBundle);
+        assert_eq!(error.message, "Cache bundle is cut off");
+
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("team.rvfxc");
+        write_bundle(&bundle, &[(HASH, "{}")], 0);
+        let data = fs::read(&bundle).unwrap();
+        fs::write(&bundle, &data[..data.len() / 2]).unwrap();
+        let error = import(&bundle, &dir.path().join("cache"), &CacheIndex::default()).unwrap_err();
+        assert!(matches!(
+            error.code,
+            ErrorCode::InvalidBundle | ErrorCode::IoError
+        ));
+    }
+}
@@ -0,0 +1,893 @@
+//! Headless command-line mode.
+//!
+//! `rivals-vfx-editor <command>` runs conversions through the same code paths
+//! as the Tauri commands, without starting a webview, so mods can be built
+//! from scripts on any platform.
+
+use crate::colors::find_color_params;
+use crate::layers::SettingsLayers;
+use crate::mappings;
+use crate::verify::RoundTripReport;
+use crate::{
+    batch_from_json, batch_to_json, cache_info, check_tool, clear_cache_files, engine_versions,
+    export_bundle, find_uasset_tool, get_cache_dir, import_bundle, load_cache_index, load_settings,
+    move_cache, remove_mapping, repair_cache_files, use_mapping, AppState, BatchConversionResult,
+    ConversionProgress, ProgressCallback,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const COMMANDS: &[&str] = &[
+    "convert",
+    "apply-session",
+    "cache",
+    "mappings",
+    "check-tool",
+    "engine-versions",
+    "help",
+    "--help",
+    "-h",
+];
+
+const USAGE: &str = "\
+Rivals VFX Editor - headless mode
+
+Usage:
+  rivals-vfx-editor <command> [options]
+
+Commands:
+  convert to-json <file-or-folder>... [--root <dir>]
+      Convert .uasset files into the conversion cache and print their JSON paths
+
+  convert from-json <file-or-folder>... --output <dir> [--root <dir>]
+      Convert JSON files back to .uasset, keeping paths relative to --root
+
+  apply-session <project.rvfxp> <uasset-folder> --output <dir> [--root <dir>]
+      Apply the colors saved in a project file and write the edited .uasset files;
+      settings come from the .rvfxe.json of --root, or else of <uasset-folder>
+
+  cache info
+      Show cache location, file count and size
+
+  cache clear
+      Delete every cached JSON file
+
+  cache move [<folder>]
+      Move the cache into <folder>, or back to the temp dir without one
+
+  cache repair
+      Reconcile the cache index with the cached files and drop broken ones
+
+  cache export <bundle>
+      Pack the cache into one file that teammates can import
+
+  cache import <bundle>
+      Add a bundle's conversions; they are used for assets with the same content
+
+  mappings list
+      List the mappings library and the version of each mapping
+
+  mappings import <file.usmap> [<label>]
+      Add a mapping to the library, labelled with its game patch
+
+  mappings use [<label>]
+      Convert with a library mapping, or with the saved .usmap path without one
+
+  mappings relabel <label> <new-label>
+      Change the label of a library mapping
+
+  mappings remove <label>
+      Delete a mapping from the library
+
+  mappings install-uassetgui [<label>]
+      Copy a mapping, the active one by default, to UAssetGUI's Rivals.usmap
+
+  check-tool
+      Show which UAssetCLI would be used and whether it starts
+
+  engine-versions
+      List the values accepted by --engine
+
+Options:
+  --usmap, -u <path>    Use this .usmap instead of the one saved in settings
+  --engine, -e <ver>    Read .uasset files as this engine version instead of the saved one
+  --root <dir>          Folder that relative output paths are computed from; its
+                        .rvfxe.json overrides the saved settings
+  --output, -o <dir>    Output folder for .uasset files
+  --jobs, -j <n>        Split large batches across n UAssetCLI processes
+  --verify              Read written .uasset files back and compare them with their JSON
+  --json                Print the full result as JSON on stdout
+  --help, -h            Show this help message
+
+Environment:
+  RVFXE_UASSET_CLI      UAssetCLI executable or UAssetCLI.dll to use
+
+Exit codes: 0 on success, 1 if any file failed, 2 on usage or setup errors.
+";
+
+/// One entry of an `.rvfxp` project file, as written by `handleExportSession`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionEntry {
+    relative_path: String,
+    param_name: String,
+    #[serde(default)]
+    rgba: Option<Value>,
+}
+
+/// An `.rvfxp` project file. Older editors wrote a bare list of entries;
+/// newer ones also pin the filter profile the project was made with.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SessionFile {
+    Pinned {
+        #[serde(rename = "filterProfile", default)]
+        filter_profile: Option<String>,
+        params: Vec<SessionEntry>,
+    },
+    Entries(Vec<SessionEntry>),
+}
+
+#[derive(Debug, Default)]
+struct CliArgs {
+    positional: Vec<String>,
+    usmap: Option<String>,
+    engine: Option<String>,
+    root: Option<String>,
+    output: Option<String>,
+    jobs: Option<usize>,
+    verify: bool,
+    json: bool,
+}
+
+/// Runs a headless subcommand if the process was started with one.
+/// Returns the exit code, or `None` when the GUI should start instead.
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first()?;
+    if !COMMANDS.contains(&command.as_str()) {
+        return None;
+    }
+
+    attach_parent_console();
+
+    Some(match run(&args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            2
+        }
+    })
+}
+
+fn run(args: &[String]) -> Result<i32, String> {
+    let command = args[0].as_str();
+    let parsed = parse_args(&args[1..])?;
+
+    match command {
+        "convert" => run_convert(parsed),
+        "apply-session" => run_apply_session(parsed),
+        "cache" => run_cache(parsed),
+        "mappings" => run_mappings(parsed),
+        "check-tool" => run_check_tool(parsed),
+        "engine-versions" => run_engine_versions(parsed),
+        _ => {
+            print!("{}", USAGE);
+            Ok(0)
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+        match arg.as_str() {
+            "--usmap" | "-u" => parsed.usmap = Some(value(arg)?),
+            "--engine" | "-e" => parsed.engine = Some(value(arg)?),
+            "--root" => parsed.root = Some(value(arg)?),
+            "--output" | "-o" => parsed.output = Some(value(arg)?),
+            "--jobs" | "-j" => {
+                let jobs = value(arg)?;
+                parsed.jobs = Some(
+                    jobs.parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("Invalid --jobs value: {}", jobs))?,
+                );
+            }
+            "--json" => parsed.json = true,
+            "--verify" => parsed.verify = true,
+            other if other.starts_with('-') && other.len() > 1 => {
+                return Err(format!("Unknown option: {}\n\n{}", other, USAGE));
+            }
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn run_convert(args: CliArgs) -> Result<i32, String> {
+    let (direction, inputs) = args
+        .positional
+        .split_first()
+        .ok_or_else(|| format!("convert needs a direction\n\n{}", USAGE))?;
+    if inputs.is_empty() {
+        return Err("convert needs at least one file or folder".to_string());
+    }
+
+    let state = load_state(&args)?;
+    let tool_path = tool_path(&state);
+
+    match direction.as_str() {
+        "to-json" => {
+            let files = collect_files(inputs, "uasset")?;
+            let root = args.root.clone().unwrap_or_else(|| default_root(inputs));
+            let result = tauri::async_runtime::block_on(batch_to_json(
+                &state,
+                &tool_path,
+                path_strings(&files),
+                &root,
+                print_progress(),
+            ))
+            .map_err(|e| e.to_string())?;
+            Ok(report(&result, true, args.json))
+        }
+        "from-json" => {
+            let output = args
+                .output
+                .clone()
+                .ok_or("convert from-json needs --output <dir>")?;
+            let files = collect_files(inputs, "json")?;
+            let root = PathBuf::from(args.root.clone().unwrap_or_else(|| default_root(inputs)));
+            let entries = files
+                .iter()
+                .map(|f| {
+                    let output_name = f
+                        .strip_prefix(&root)
+                        .unwrap_or_else(|_| Path::new(f.file_name().unwrap_or_default()))
+                        .with_extension("uasset");
+                    format!("{},{}", f.to_string_lossy(), output_name.to_string_lossy())
+                })
+                .collect();
+            let result = tauri::async_runtime::block_on(batch_from_json(
+                &state,
+                &tool_path,
+                entries,
+                &output,
+                print_progress(),
+            ))
+            .map_err(|e| e.to_string())?;
+            Ok(report(&result, false, args.json))
+        }
+        other => Err(format!(
+            "Unknown convert direction '{}', expected to-json or from-json",
+            other
+        )),
+    }
+}
+
+fn run_apply_session(mut args: CliArgs) -> Result<i32, String> {
+    let [session_path, folder] = args.positional.clone().try_into().map_err(|_| {
+        format!(
+            "apply-session needs <project.rvfxp> <uasset-folder>\n\n{}",
+            USAGE
+        )
+    })?;
+    // Like opening the folder in the editor, its .rvfxe.json applies unless
+    // --root names another project
+    args.root.get_or_insert_with(|| folder.clone());
+    let output = args
+        .output
+        .clone()
+        .ok_or("apply-session needs --output <dir>")?;
+
+    let session_content = fs::read_to_string(&session_path)
+        .map_err(|e| format!("Failed to read {}: {}", session_path, e))?;
+    let (filter_profile, session) = match serde_json::from_str(&session_content)
+        .map_err(|e| format!("Invalid project file format: {}", e))?
+    {
+        SessionFile::Pinned {
+            filter_profile,
+            params,
+        } => (filter_profile, params),
+        SessionFile::Entries(entries) => (None, entries),
+    };
+
+    let state = load_state(&args)?;
+    let tool_path = tool_path(&state);
+    let color_property_names = {
+        let settings = state.settings.lock().unwrap();
+        let profiles = &settings.filter_profiles;
+        let dictionary = match filter_profile {
+            Some(name) => profiles.get(&name).unwrap_or_else(|_| {
+                eprintln!(
+                    "Project uses filter profile \"{}\", which does not exist here; using \"{}\"",
+                    name, profiles.active
+                );
+                profiles.active()
+            }),
+            None => profiles.active(),
+        };
+        dictionary.color_property_names.clone()
+    };
+
+    let uassets = collect_files(std::slice::from_ref(&folder), "uasset")?;
+    let to_json = tauri::async_runtime::block_on(batch_to_json(
+        &state,
+        &tool_path,
+        path_strings(&uassets),
+        &folder,
+        print_progress(),
+    ))
+    .map_err(|e| e.to_string())?;
+    if to_json.failed > 0 {
+        eprintln!("{} file(s) could not be converted to JSON", to_json.failed);
+    }
+
+    // Edited JSON goes to a scratch folder so the cache keeps the originals
+    let scratch = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    let mut updated_params = 0;
+
+    for converted in to_json.results.iter().filter(|r| r.success) {
+        let Some(json_path) = &converted.json_path else {
+            continue;
+        };
+        let relative = Path::new(&converted.uasset_path)
+            .strip_prefix(&folder)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| PathBuf::from(&converted.file_name));
+
+        let content = fs::read_to_string(json_path).map_err(|e| e.to_string())?;
+        let mut json: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", json_path, e))?;
+
+        let relative_key = normalize_session_path(&relative.to_string_lossy());
+        let mut updated_in_file = 0;
+        for param in find_color_params(&json, &color_property_names) {
+            let Some(rgba) = find_session_entry(&session, &relative_key, &param.param_name)
+                .and_then(|entry| entry.rgba.as_ref())
+            else {
+                continue;
+            };
+            if let Some(Value::Object(target)) = json.pointer_mut(&param.pointer) {
+                for channel in ["R", "G", "B", "A"] {
+                    if let Some(value) = rgba.get(channel) {
+                        target.insert(channel.to_string(), value.clone());
+                    }
+                }
+                updated_in_file += 1;
+            }
+        }
+
+        if updated_in_file == 0 {
+            continue;
+        }
+        updated_params += updated_in_file;
+
+        let edited_path = scratch.path().join(&relative).with_extension("json");
+        if let Some(parent) = edited_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let edited = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+        fs::write(&edited_path, edited).map_err(|e| e.to_string())?;
+        entries.push(format!(
+            "{},{}",
+            edited_path.to_string_lossy(),
+            relative.with_extension("uasset").to_string_lossy()
+        ));
+    }
+
+    eprintln!(
+        "Applied {} parameter value(s) across {} file(s)",
+        updated_params,
+        entries.len()
+    );
+    if entries.is_empty() {
+        return Ok(if to_json.failed > 0 { 1 } else { 0 });
+    }
+
+    let from_json = tauri::async_runtime::block_on(batch_from_json(
+        &state,
+        &tool_path,
+        entries,
+        &output,
+        print_progress(),
+    ))
+    .map_err(|e| e.to_string())?;
+    let code = report(&from_json, false, args.json);
+    Ok(if to_json.failed > 0 { 1 } else { code })
+}
+
+fn run_cache(args: CliArgs) -> Result<i32, String> {
+    match args.positional.first().map(String::as_str) {
+        Some("info") => {
+            let info = cache_info(&load_state(&args)?);
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?
+                );
+            } else {
+                println!("Cache dir:  {}", info.cache_dir);
+                println!("Files:      {}", info.file_count);
+                println!("Total size: {} bytes", info.total_size_bytes);
+                println!("Evictable:  {} bytes", info.evictable_bytes);
+                match info.max_cache_bytes {
+                    Some(max) => println!("Limit:      {} bytes", max),
+                    None => println!("Limit:      none"),
+                }
+            }
+            Ok(0)
+        }
+        Some("clear") => {
+            let state = load_state(&args)?;
+            clear_cache_files(&state).map_err(|e| e.to_string())?;
+            println!("Cache cleared");
+            Ok(0)
+        }
+        Some("move") => {
+            let state = load_state(&args)?;
+            let location = args.positional.get(1).cloned();
+            let report = move_cache(&state, location).map_err(|e| e.to_string())?;
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+                );
+            } else {
+                println!(
+                    "Moved {} files ({} bytes) to {}",
+                    report.moved_files, report.moved_bytes, report.cache_dir
+                );
+            }
+            Ok(0)
+        }
+        Some("repair") => {
+            let state = load_state(&args)?;
+            let tool_path = tool_path(&state);
+            let report = tauri::async_runtime::block_on(repair_cache_files(&state, &tool_path))
+                .map_err(|e| e.to_string())?;
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+                );
+            } else {
+                println!("Missing JSON:    {}", report.missing_json.len());
+                println!("Unreadable JSON: {}", report.unreadable.len());
+                println!("Rebuilt entries: {}", report.rebuilt.len());
+                println!("Orphans removed: {}", report.orphans_removed.len());
+                println!("Freed:           {} bytes", report.freed_bytes);
+            }
+            Ok(0)
+        }
+        Some(action @ ("export" | "import")) => {
+            let Some(bundle_path) = args.positional.get(1).map(PathBuf::from) else {
+                return Err(format!("cache {} needs a bundle file\n\n{}", action, USAGE));
+            };
+            let state = load_state(&args)?;
+            let report = if action == "export" {
+                tauri::async_runtime::block_on(export_bundle(&state, bundle_path))
+            } else {
+                tauri::async_runtime::block_on(import_bundle(&state, bundle_path))
+            }
+            .map_err(|e| e.to_string())?;
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+                );
+            } else if action == "export" {
+                println!(
+                    "Exported {} entries ({} bytes) to {}",
+                    report.entries, report.bytes, report.bundle_path
+                );
+            } else {
+                println!(
+                    "Imported {} entries ({} bytes), {} already cached",
+                    report.entries, report.bytes, report.skipped
+                );
+            }
+            Ok(0)
+        }
+        _ => Err(format!(
+            "cache needs 'info', 'clear', 'move', 'repair', 'export' or 'import'\n\n{}",
+            USAGE
+        )),
+    }
+}
+
+fn run_mappings(args: CliArgs) -> Result<i32, String> {
+    let state = load_state(&args)?;
+    let active = state.settings.lock().unwrap().active_mapping.clone();
+    let name = args.positional.get(1).map(String::as_str);
+    match (args.positional.first().map(String::as_str), name) {
+        (Some("list"), _) => {
+            let list = mappings::list(active.as_deref()).map_err(|e| e.to_string())?;
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?
+                );
+            } else if list.is_empty() {
+                println!("No mappings imported");
+            }
+            for mapping in list.iter().filter(|_| !args.json) {
+                let marker = if mapping.active { "*" } else { " " };
+                let version = match (&mapping.summary, &mapping.error) {
+                    (Some(summary), _) => format!(
+                        "{:?} (v{}), {} structs",
+                        summary.version, summary.version_number, summary.struct_count
+                    ),
+                    (None, Some(error)) => error.clone(),
+                    (None, None) => String::new(),
+                };
+                println!(
+                    "{} {:<12} {}  {}",
+                    marker,
+                    mapping.entry.label,
+                    mapping.entry.hash.get(..12).unwrap_or(&mapping.entry.hash),
+                    version
+                );
+            }
+            Ok(0)
+        }
+        (Some("import"), Some(file)) => {
+            let label = args.positional.get(2).map(String::as_str);
+            let import = mappings::import(Path::new(file), label, active.as_deref())
+                .map_err(|e| e.to_string())?;
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&import).map_err(|e| e.to_string())?
+                );
+            } else if import.duplicate {
+                println!(
+                    "Already in the library as \"{}\"",
+                    import.mapping.entry.label
+                );
+            } else {
+                println!(
+                    "Imported \"{}\" ({})",
+                    import.mapping.entry.label, import.mapping.entry.hash
+                );
+            }
+            Ok(0)
+        }
+        (Some("use"), name) => {
+            use_mapping(&state, name).map_err(|e| e.to_string())?;
+            match name {
+                Some(name) => println!("Converting with mapping \"{}\"", name),
+                None => println!("Converting with the saved .usmap path"),
+            }
+            Ok(0)
+        }
+        (Some("relabel"), Some(name)) => {
+            let Some(label) = args.positional.get(2) else {
+                return Err(format!("mappings relabel needs a new label\n\n{}", USAGE));
+            };
+            let entry = mappings::relabel(name, label).map_err(|e| e.to_string())?;
+            println!(
+                "Relabelled {} to \"{}\"",
+                entry.hash.get(..12).unwrap_or(&entry.hash),
+                entry.label
+            );
+            Ok(0)
+        }
+        (Some("remove"), Some(name)) => {
+            let entry = remove_mapping(&state, name).map_err(|e| e.to_string())?;
+            println!("Removed \"{}\"", entry.label);
+            Ok(0)
+        }
+        (Some("install-uassetgui"), name) => {
+            let Some(name) = name.or(active.as_deref()) else {
+                return Err("No library mapping is active, name one to install".to_string());
+            };
+            let target = mappings::install_for_uassetgui(name).map_err(|e| e.to_string())?;
+            println!("Installed to {}", target);
+            Ok(0)
+        }
+        (Some(action @ ("import" | "relabel" | "remove")), None) => Err(format!(
+            "mappings {} needs a mapping{}\n\n{}",
+            action,
+            if action == "import" { " file" } else { " label" },
+            USAGE
+        )),
+        _ => Err(format!(
+            "mappings needs 'list', 'import', 'use', 'relabel', 'remove' or 'install-uassetgui'\n\n{}",
+            USAGE
+        )),
+    }
+}
+
+fn run_check_tool(args: CliArgs) -> Result<i32, String> {
+    let configured = load_state(&args)?
+        .settings
+        .lock()
+        .unwrap()
+        .uasset_cli_path
+        .clone();
+    let location = find_uasset_tool(None, configured);
+    let info = tauri::async_runtime::block_on(check_tool(&location));
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?
+        );
+    } else {
+        println!("Path:     {} ({})", info.path, info.source);
+        println!(
+            "Runtime:  {}{}",
+            info.runtime,
+            info.runtime_version
+                .as_deref()
+                .map(|v| format!(" {}", v))
+                .unwrap_or_default()
+        );
+        println!(
+            "Version:  {}",
+            info.tool_version.as_deref().unwrap_or("unknown")
+        );
+        if let Some(error) = &info.error {
+            println!("Error:    {}", error);
+        }
+    }
+
+    Ok(if info.error.is_some() { 1 } else { 0 })
+}
+
+fn run_engine_versions(args: CliArgs) -> Result<i32, String> {
+    let state = load_state(&args)?;
+    let tool_path = tool_path(&state);
+    let list = tauri::async_runtime::block_on(engine_versions(&state, &tool_path))
+        .map_err(|e| e.to_string())?;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?
+        );
+    } else {
+        let current = state.settings.lock().unwrap().engine_version.clone();
+        for version in &list.versions {
+            let marker = if *version == current { "*" } else { " " };
+            println!("{} {}", marker, version);
+        }
+    }
+    Ok(0)
+}
+
+fn tool_path(state: &AppState) -> PathBuf {
+    let configured = state.settings.lock().unwrap().uasset_cli_path.clone();
+    find_uasset_tool(None, configured).path
+}
+
+/// Loads the saved settings with the command-line overrides applied. An
+/// unreadable project file is an error: converting with the global usmap
+/// or engine version instead would go unnoticed in a script.
+fn load_state(args: &CliArgs) -> Result<AppState, String> {
+    let mut layers = SettingsLayers::new(load_settings());
+    if let Some(root) = &args.root {
+        layers
+            .open_project(Some(Path::new(root)))
+            .map_err(|e| e.to_string())?;
+    }
+    let mut settings = layers.effective();
+    if args.usmap.is_some() {
+        settings.usmap_path = args.usmap.clone();
+        settings.active_mapping = None;
+    }
+    if let Some(engine) = &args.engine {
+        settings.engine_version = engine.clone();
+    }
+    if let Some(jobs) = args.jobs {
+        settings.conversion_concurrency = jobs;
+    }
+    if args.verify {
+        settings.verify_round_trip = true;
+    }
+    let cache_index = load_cache_index(&get_cache_dir(&settings));
+    let state = AppState::new(layers, cache_index);
+    // Flags only apply to this run, so they never reach a settings file
+    *state.settings.lock().unwrap() = settings;
+    Ok(state)
+}
+
+fn print_progress() -> ProgressCallback {
+    Arc::new(|progress: &ConversionProgress| {
+        let status = match (&progress.error, progress.cached) {
+            (Some(error), _) => format!("FAILED: {}", error),
+            (None, true) => "cached".to_string(),
+            (None, false) => "ok".to_string(),
+        };
+        eprintln!(
+            "[{}/{}] {} ({})",
+            progress.current, progress.total, progress.file_name, status
+        );
+    })
+}
+
+/// Prints the outcome of a batch and returns the matching exit code;
+/// `to_json` picks which path is the output.
+fn report(result: &BatchConversionResult, to_json: bool, as_json: bool) -> i32 {
+    if as_json {
+        match serde_json::to_string_pretty(result) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("error: {}", e),
+        }
+    } else {
+        for single in &result.results {
+            let output = if to_json {
+                single.json_path.as_deref()
+            } else {
+                Some(single.uasset_path.as_str())
+            };
+            match (output, &single.error) {
+                (_, Some(error)) if !single.success => {
+                    println!("FAILED {}: {}", single.file_name, error)
+                }
+                (Some(path), _) if single.success => println!("{}", path),
+                _ => {}
+            }
+            if let Some(report) = single.round_trip.as_ref().filter(|r| !r.is_clean()) {
+                print_round_trip(&single.file_name, report);
+            }
+        }
+        println!(
+            "{} total, {} succeeded ({} cached), {} failed",
+            result.total, result.succeeded, result.cached_count, result.failed
+        );
+    }
+
+    let unverified = result
+        .results
+        .iter()
+        .any(|r| r.round_trip.as_ref().is_some_and(|r| !r.is_clean()));
+    if result.failed > 0 || unverified {
+        1
+    } else {
+        0
+    }
+}
+
+fn print_round_trip(file_name: &str, report: &RoundTripReport) {
+    if let Some(error) = &report.error {
+        println!("VERIFY FAILED {}: {}", file_name, error);
+        return;
+    }
+    println!(
+        "VERIFY FAILED {}: {} mismatched propert{}",
+        file_name,
+        report.mismatch_count,
+        if report.mismatch_count == 1 {
+            "y"
+        } else {
+            "ies"
+        }
+    );
+    for mismatch in &report.mismatches {
+        let show = |v: &Option<Value>| v.as_ref().map_or("(missing)".to_string(), Value::to_string);
+        println!(
+            "  {}: expected {}, got {}",
+            mismatch.pointer,
+            show(&mismatch.expected),
+            show(&mismatch.actual)
+        );
+    }
+    if report.mismatch_count > report.mismatches.len() {
+        println!("  ...");
+    }
+}
+
+/// Expands folders recursively and keeps explicitly listed files as-is.
+fn collect_files(inputs: &[String], extension: &str) -> Result<Vec<PathBuf>, String> {
+    fn walk(dir: &Path, extension: &str, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, extension, files)?;
+            } else if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+            {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = PathBuf::from(input);
+        if path.is_dir() {
+            walk(&path, extension, &mut files).map_err(|e| format!("{}: {}", input, e))?;
+        } else if path.is_file() {
+            files.push(path);
+        } else {
+            return Err(format!("File not found: {}", input));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Mirrors the drop handler: a dropped folder is its own root, loose files
+/// use their parent folder.
+fn default_root(inputs: &[String]) -> String {
+    let first = Path::new(&inputs[0]);
+    let root = if first.is_dir() {
+        first
+    } else {
+        first.parent().unwrap_or(first)
+    };
+    root.to_string_lossy().to_string()
+}
+
+fn path_strings(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Same normalization as `normalizePath` in `handleImportSession`.
+fn normalize_session_path(path: &str) -> String {
+    let path = path.replace('\\', "/").to_lowercase();
+    let path = path.strip_suffix(".json").unwrap_or(&path);
+    path.strip_suffix(".uasset").unwrap_or(path).to_string()
+}
+
+/// Exact path match first, then a suffix match for different import roots,
+/// then file name only, like the project import in the editor.
+fn find_session_entry<'a>(
+    session: &'a [SessionEntry],
+    relative_key: &str,
+    param_name: &str,
+) -> Option<&'a SessionEntry> {
+    let file_name = |p: &str| p.rsplit('/').next().unwrap_or_default().to_string();
+    let candidates = || {
+        session
+            .iter()
+            .filter(move |e| e.param_name == param_name)
+            .map(|e| (e, normalize_session_path(&e.relative_path)))
+    };
+
+    candidates()
+        .find(|(_, path)| path == relative_key)
+        .or_else(|| {
+            candidates().find(|(_, path)| {
+                path.ends_with(relative_key) || relative_key.ends_with(path.as_str())
+            })
+        })
+        .or_else(|| candidates().find(|(_, path)| file_name(path) == file_name(relative_key)))
+        .map(|(entry, _)| entry)
+}
+
+/// Release builds use the Windows GUI subsystem, so reattach to the console
+/// that launched us or nothing we print would be visible.
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
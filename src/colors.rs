@@ -0,0 +1,670 @@
+//! Color parameter discovery in UAssetAPI JSON.
+//!
+//! Three layouts are recognized: a material instance's
+//! `VectorParameterValues`, `RichTextStyleRow` DataTables, and the `Data` of
+//! any other export, searched for the LinearColor properties the filter
+//! dictionary names. Parameter names follow the webview's original parser so
+//! they line up with the ones saved in `.rvfxp` project files.
+//!
+//! The headless `apply-session` needs it to find those parameters without
+//! the webview; the editor itself reads them from here as well.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+
+use crate::error::{AppError, ErrorCode};
+use crate::filter::FilterMatcher;
+
+const DATA_TABLE_EXPORT_TYPE: &str = "UAssetAPI.ExportTypes.DataTableExport, UAssetAPI";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColorParam {
+    pub param_name: String,
+    /// JSON pointer to the LinearColor object holding R/G/B/A.
+    pub pointer: String,
+    pub rgba: Rgba,
+}
+
+/// Linear color channels of a parameter.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct Rgba {
+    #[serde(rename = "R")]
+    pub r: f64,
+    #[serde(rename = "G")]
+    pub g: f64,
+    #[serde(rename = "B")]
+    pub b: f64,
+    #[serde(rename = "A")]
+    pub a: f64,
+}
+
+/// Which of the supported layouts the parameters were found in.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetType {
+    MaterialInstance,
+    RichTextTable,
+    Generic,
+}
+
+/// Everything the editor needs to list an asset's colors without loading
+/// its JSON.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParamSummary {
+    pub asset_type: AssetType,
+    pub params: Vec<ColorParam>,
+}
+
+/// A file `extract_color_params` reads: a cached JSON by path, or the text of
+/// one the webview opened itself.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ColorSource {
+    /// Path the file is shown and saved under, e.g. `Effects/M_Fire.json`.
+    pub relative_path: String,
+    #[serde(default)]
+    pub json_path: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+impl ColorSource {
+    pub fn read(&self) -> Result<Value, AppError> {
+        let invalid = |e: serde_json::Error| {
+            AppError::new(
+                ErrorCode::InvalidJson,
+                format!("{}: {}", self.relative_path, e),
+            )
+        };
+        match (&self.content, &self.json_path) {
+            (Some(content), _) => serde_json::from_str(content).map_err(invalid),
+            (None, Some(json_path)) => {
+                let content = fs::read(json_path).map_err(|e| AppError::io(json_path, e))?;
+                serde_json::from_slice(&content).map_err(|e| invalid(e).with_path(json_path))
+            }
+            (None, None) => Err(AppError::new(
+                ErrorCode::InvalidJson,
+                format!("{}: no JSON path or content given", self.relative_path),
+            )),
+        }
+    }
+}
+
+/// A color parameter as `extract_color_params` lists it.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExtractedParam {
+    /// `<relative path>#<pointer>`, the same every time the file is loaded.
+    pub id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub param_name: String,
+    /// RFC 6901 pointer to the LinearColor object holding R/G/B/A.
+    pub pointer: String,
+    pub source_format: AssetType,
+    pub rgba: Rgba,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExtractionFailure {
+    pub relative_path: String,
+    pub error: AppError,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ColorExtraction {
+    /// Parameters the filter kept, in file order.
+    pub params: Vec<ExtractedParam>,
+    /// Parameters the filter dropped.
+    pub filtered_out: usize,
+    pub failed: Vec<ExtractionFailure>,
+}
+
+/// Lists the color parameters of one converted asset that `matcher` keeps,
+/// and how many it dropped.
+pub fn extract_params(
+    json: &Value,
+    relative_path: &str,
+    color_property_names: &[String],
+    matcher: &FilterMatcher,
+) -> (Vec<ExtractedParam>, usize) {
+    let summary = summarize_colors(json, color_property_names);
+    let file_name = relative_path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(relative_path);
+    let total = summary.params.len();
+    let params: Vec<_> = summary
+        .params
+        .into_iter()
+        .filter(|param| matcher.decide(&param.param_name).matched)
+        .map(|param| ExtractedParam {
+            id: format!("{}#{}", relative_path, param.pointer),
+            file_name: file_name.to_string(),
+            relative_path: relative_path.to_string(),
+            param_name: param.param_name,
+            pointer: param.pointer,
+            source_format: summary.asset_type,
+            rgba: param.rgba,
+        })
+        .collect();
+    let filtered_out = total - params.len();
+    (params, filtered_out)
+}
+
+/// Finds every color parameter in a converted asset.
+pub fn find_color_params(json: &Value, color_property_names: &[String]) -> Vec<ColorParam> {
+    summarize_colors(json, color_property_names).params
+}
+
+/// Finds every color parameter in a converted asset, along with its layout.
+pub fn summarize_colors(json: &Value, color_property_names: &[String]) -> ParamSummary {
+    let mut params = Vec::new();
+    let first_export = json.pointer("/Exports/0");
+
+    // FORMAT TYPE 1: VFX material instance
+    let vector_params = first_export
+        .and_then(|e| e.get("Data"))
+        .and_then(Value::as_array)
+        .and_then(|data| {
+            data.iter()
+                .position(|p| name_of(p) == Some("VectorParameterValues"))
+                .map(|i| (i, &data[i]))
+        });
+    if let Some((data_index, Some(values))) =
+        vector_params.map(|(i, p)| (i, p.get("Value").and_then(Value::as_array)))
+    {
+        for (param_index, param) in values.iter().enumerate() {
+            let Some(fields) = param.get("Value").and_then(Value::as_array) else {
+                continue;
+            };
+            let param_name = find_named(fields, "ParameterInfo")
+                .and_then(|(_, info)| info.get("Value").and_then(Value::as_array))
+                .and_then(|info| find_named(info, "Name"))
+                .and_then(|(_, name)| name.get("Value").and_then(Value::as_str));
+            let Some(param_name) = param_name else {
+                continue;
+            };
+            let Some((value_index, value_obj)) = find_named(fields, "ParameterValue") else {
+                continue;
+            };
+            let color = value_obj
+                .get("Value")
+                .and_then(Value::as_array)
+                .and_then(|v| find_named(v, "ParameterValue"))
+                .and_then(|(i, c)| c.get("Value").map(|v| (i, v)));
+            if let Some((color_index, color)) = color.filter(|(_, c)| is_linear_color(c)) {
+                params.push(ColorParam {
+                    param_name: param_name.to_string(),
+                    pointer: format!(
+                        "/Exports/0/Data/{}/Value/{}/Value/{}/Value/{}/Value",
+                        data_index, param_index, value_index, color_index
+                    ),
+                    rgba: rgba_of(color),
+                });
+            }
+        }
+        return ParamSummary {
+            asset_type: AssetType::MaterialInstance,
+            params,
+        };
+    }
+
+    // FORMAT TYPE 2: RichText style DataTables
+    let table_rows = first_export
+        .filter(|e| e.get("$type").and_then(Value::as_str) == Some(DATA_TABLE_EXPORT_TYPE))
+        .and_then(|e| e.pointer("/Table/Data"))
+        .and_then(Value::as_array);
+    if let Some(rows) = table_rows {
+        for (row_index, row) in rows.iter().enumerate() {
+            if row.get("StructType").and_then(Value::as_str) != Some("RichTextStyleRow") {
+                continue;
+            }
+            if let Some(value) = row.get("Value") {
+                let style_name = name_of(row).unwrap_or_default();
+                find_recursive(
+                    value,
+                    format!("/Exports/0/Table/Data/{}/Value", row_index),
+                    style_name,
+                    color_property_names,
+                    &mut params,
+                );
+            }
+        }
+        return ParamSummary {
+            asset_type: AssetType::RichTextTable,
+            params,
+        };
+    }
+
+    // FORMAT TYPE 3: generic exports
+    if let Some(exports) = json.get("Exports").and_then(Value::as_array) {
+        for (export_index, export) in exports.iter().enumerate() {
+            let Some(data) = export.get("Data").filter(|d| d.is_array()) else {
+                continue;
+            };
+            let parent_name = export
+                .get("ObjectName")
+                .and_then(Value::as_str)
+                .filter(|n| !n.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("Export_{}", export_index));
+            find_recursive(
+                data,
+                format!("/Exports/{}/Data", export_index),
+                &parent_name,
+                color_property_names,
+                &mut params,
+            );
+        }
+    }
+
+    ParamSummary {
+        asset_type: AssetType::Generic,
+        params,
+    }
+}
+
+fn find_recursive(
+    value: &Value,
+    pointer: String,
+    parent_name: &str,
+    color_property_names: &[String],
+    params: &mut Vec<ColorParam>,
+) {
+    let is_color_property = name_of(value)
+        .is_some_and(|n| color_property_names.iter().any(|c| c == n))
+        && value.get("StructType").and_then(Value::as_str) == Some("LinearColor");
+    let color = value
+        .pointer("/Value/0/Value")
+        .filter(|c| is_linear_color(c));
+
+    if let Some(color) = color.filter(|_| is_color_property) {
+        params.push(ColorParam {
+            param_name: format!("{} - {}", parent_name, name_of(value).unwrap_or_default()),
+            pointer: format!("{}/Value/0/Value", pointer),
+            rgba: rgba_of(color),
+        });
+        return;
+    }
+
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                find_recursive(
+                    item,
+                    format!("{}/{}", pointer, i),
+                    parent_name,
+                    color_property_names,
+                    params,
+                );
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                find_recursive(
+                    item,
+                    format!("{}/{}", pointer, escape_pointer_token(key)),
+                    parent_name,
+                    color_property_names,
+                    params,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn name_of(value: &Value) -> Option<&str> {
+    value.get("Name").and_then(Value::as_str)
+}
+
+fn find_named<'a>(items: &'a [Value], name: &str) -> Option<(usize, &'a Value)> {
+    items
+        .iter()
+        .enumerate()
+        .find(|(_, item)| name_of(item) == Some(name))
+}
+
+/// The frontend treats any object with an `R` channel as a LinearColor value.
+fn is_linear_color(value: &Value) -> bool {
+    value.get("R").is_some()
+}
+
+/// Reads channels like the frontend's `parseFloat(x) || 0`, except that
+/// non-finite values also become 0 since JSON cannot carry them.
+fn rgba_of(color: &Value) -> Rgba {
+    let channel = |name: &str| {
+        let value = match color.get(name) {
+            Some(Value::Number(n)) => n.as_f64(),
+            Some(Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        };
+        value.filter(|v: &f64| v.is_finite()).unwrap_or(0.0)
+    };
+    Rgba {
+        r: channel("R"),
+        g: channel("G"),
+        b: channel("B"),
+        a: channel("A"),
+    }
+}
+
+/// Escapes one reference token of a JSON pointer (RFC 6901).
+pub(crate) fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::{FilterRule, RuleAction, RuleKind};
+    use crate::FilterDictionary;
+    use serde_json::json;
+
+    fn linear_color(name: &str, r: Value, g: f64, b: f64, a: f64) -> Value {
+        json!({
+            "$type": "UAssetAPI.PropertyTypes.Structs.StructPropertyData, UAssetAPI",
+            "StructType": "LinearColor",
+            "Name": name,
+            "Value": [{
+                "$type": "UAssetAPI.PropertyTypes.Structs.LinearColorPropertyData, UAssetAPI",
+                "Name": name,
+                "Value": { "$type": "UAssetAPI.UnrealTypes.FLinearColor, UAssetAPI", "R": r, "G": g, "B": b, "A": a }
+            }]
+        })
+    }
+
+    fn vector_parameter(name: &str, r: Value, g: f64, b: f64) -> Value {
+        json!({
+            "$type": "UAssetAPI.PropertyTypes.Structs.StructPropertyData, UAssetAPI",
+            "StructType": "VectorParameterValue",
+            "Name": "VectorParameterValues",
+            "Value": [
+                {
+                    "StructType": "MaterialParameterInfo",
+                    "Name": "ParameterInfo",
+                    "Value": [
+                        { "Name": "Name", "Value": name },
+                        { "Name": "Association", "Value": "EMaterialParameterAssociation::GlobalParameter" },
+                        { "Name": "Index", "Value": -1 }
+                    ]
+                },
+                linear_color("ParameterValue", r, g, b, 1.0),
+                { "Name": "ExpressionGUID", "Value": "{00000000-0000-0000-0000-000000000000}" }
+            ]
+        })
+    }
+
+    fn material_instance() -> Value {
+        json!({
+            "Info": "Serialized with UAssetAPI",
+            "Exports": [{
+                "$type": "UAssetAPI.ExportTypes.NormalExport, UAssetAPI",
+                "ObjectName": "MI_Fire",
+                "Data": [
+                    { "Name": "Parent", "Value": -3 },
+                    {
+                        "$type": "UAssetAPI.PropertyTypes.Objects.ArrayPropertyData, UAssetAPI",
+                        "ArrayType": "StructProperty",
+                        "Name": "VectorParameterValues",
+                        "Value": [
+                            vector_parameter("Glow Color", json!(4.0), 1.5, 0.25),
+                            vector_parameter("Enemy Tint", json!("0.5"), 0.0, 0.0),
+                            { "Name": "Broken" }
+                        ]
+                    }
+                ]
+            }]
+        })
+    }
+
+    fn rich_text_table() -> Value {
+        json!({
+            "Exports": [{
+                "$type": DATA_TABLE_EXPORT_TYPE,
+                "ObjectName": "DT_RichTextStyles",
+                "Data": [],
+                "Table": {
+                    "Data": [
+                        {
+                            "StructType": "RichTextStyleRow",
+                            "Name": "Damage",
+                            "Value": [{
+                                "StructType": "TextBlockStyle",
+                                "Name": "TextStyle",
+                                "Value": [
+                                    linear_color("ColorAndOpacity", json!(1.0), 0.1, 0.1, 1.0),
+                                    linear_color("ShadowColorAndOpacity", json!(0.0), 0.0, 0.0, 1.0)
+                                ]
+                            }]
+                        },
+                        {
+                            "StructType": "SomeOtherRow",
+                            "Name": "Ignored",
+                            "Value": [linear_color("Color", json!(1.0), 1.0, 1.0, 1.0)]
+                        },
+                        {
+                            "StructType": "RichTextStyleRow",
+                            "Name": "Heal",
+                            "Value": [linear_color("ShadowColor", json!(0.0), 1.0, 0.0, 0.5)]
+                        }
+                    ]
+                }
+            }]
+        })
+    }
+
+    fn generic_blueprint() -> Value {
+        json!({
+            "Exports": [
+                {
+                    "ObjectName": "WBP_Crosshair",
+                    "Data": [{
+                        "Name": "Appearance",
+                        "Value": {
+                            "Slate/Brush": [linear_color("TintColor", json!(1.0), 1.0, 1.0, 1.0)],
+                            "Tilde~Key": linear_color("Color", json!(0.2), 0.4, 0.6, 0.8)
+                        }
+                    }]
+                },
+                { "ObjectName": "NoData" },
+                {
+                    "ObjectName": "",
+                    "Data": [linear_color("BackgroundColor", json!(0.0), 0.0, 1.0, 1.0)]
+                }
+            ]
+        })
+    }
+
+    fn dictionary() -> FilterDictionary {
+        FilterDictionary {
+            include_keywords: Vec::new(),
+            exclude_keywords: Vec::new(),
+            ..FilterDictionary::default()
+        }
+    }
+
+    fn extract(json: &Value, dictionary: &FilterDictionary) -> (Vec<ExtractedParam>, usize) {
+        let matcher = FilterMatcher::new(dictionary).unwrap();
+        extract_params(
+            json,
+            "Effects/Hero/M_Test.json",
+            &dictionary.color_property_names,
+            &matcher,
+        )
+    }
+
+    fn names(params: &[ExtractedParam]) -> Vec<&str> {
+        params.iter().map(|p| p.param_name.as_str()).collect()
+    }
+
+    /// Every pointer must lead to the color it was read from.
+    fn assert_pointers_resolve(json: &Value, params: &[ExtractedParam]) {
+        for param in params {
+            let color = json
+                .pointer(&param.pointer)
+                .unwrap_or_else(|| panic!("{} does not resolve", param.pointer));
+            assert_eq!(rgba_of(color), param.rgba, "{}", param.pointer);
+        }
+    }
+
+    #[test]
+    fn material_instance_vector_parameters() {
+        let json = material_instance();
+        let (params, filtered_out) = extract(&json, &dictionary());
+
+        assert_eq!(names(&params), ["Glow Color", "Enemy Tint"]);
+        assert_eq!(filtered_out, 0);
+        assert!(params
+            .iter()
+            .all(|p| p.source_format == AssetType::MaterialInstance));
+        assert_eq!(
+            params[0].pointer,
+            "/Exports/0/Data/1/Value/0/Value/1/Value/0/Value"
+        );
+        assert_eq!(
+            params[0].rgba,
+            Rgba {
+                r: 4.0,
+                g: 1.5,
+                b: 0.25,
+                a: 1.0
+            }
+        );
+        // String channels are read like the frontend's parseFloat
+        assert_eq!(params[1].rgba.r, 0.5);
+        assert_pointers_resolve(&json, &params);
+    }
+
+    #[test]
+    fn rich_text_style_rows() {
+        let json = rich_text_table();
+        let mut dictionary = dictionary();
+        dictionary.color_property_names = vec![
+            "ColorAndOpacity".to_string(),
+            "ShadowColor".to_string(),
+            "Color".to_string(),
+        ];
+        let (params, _) = extract(&json, &dictionary);
+
+        // Other row types and properties outside the list are skipped
+        assert_eq!(
+            names(&params),
+            ["Damage - ColorAndOpacity", "Heal - ShadowColor"]
+        );
+        assert!(params
+            .iter()
+            .all(|p| p.source_format == AssetType::RichTextTable));
+        assert_eq!(
+            params[1].pointer,
+            "/Exports/0/Table/Data/2/Value/0/Value/0/Value"
+        );
+        assert_pointers_resolve(&json, &params);
+    }
+
+    #[test]
+    fn generic_export_data() {
+        let json = generic_blueprint();
+        let mut dictionary = dictionary();
+        dictionary.color_property_names = vec![
+            "TintColor".to_string(),
+            "Color".to_string(),
+            "BackgroundColor".to_string(),
+        ];
+        let (params, _) = extract(&json, &dictionary);
+
+        let mut found = names(&params);
+        found.sort_unstable();
+        assert_eq!(
+            found,
+            [
+                "Export_2 - BackgroundColor",
+                "WBP_Crosshair - Color",
+                "WBP_Crosshair - TintColor",
+            ]
+        );
+        assert!(params.iter().all(|p| p.source_format == AssetType::Generic));
+        // Object keys are escaped as RFC 6901 requires
+        let pointers: Vec<_> = params.iter().map(|p| p.pointer.as_str()).collect();
+        assert!(pointers.contains(&"/Exports/0/Data/0/Value/Slate~1Brush/0/Value/0/Value"));
+        assert!(pointers.contains(&"/Exports/0/Data/0/Value/Tilde~0Key/Value/0/Value"));
+        assert_pointers_resolve(&json, &params);
+    }
+
+    #[test]
+    fn filter_dictionary_is_applied() {
+        let json = material_instance();
+        let mut dictionary = dictionary();
+        dictionary.exclude_keywords = vec!["enemy".to_string()];
+        let (params, filtered_out) = extract(&json, &dictionary);
+        assert_eq!(names(&params), ["Glow Color"]);
+        assert_eq!(filtered_out, 1);
+
+        dictionary.rules = vec![FilterRule {
+            kind: RuleKind::Exact,
+            pattern: "Enemy Tint".to_string(),
+            action: RuleAction::Allow,
+            priority: 1,
+            case_sensitive: true,
+        }];
+        let (params, filtered_out) = extract(&json, &dictionary);
+        // The allow rule outranks the keyword and drops everything else
+        assert_eq!(names(&params), ["Enemy Tint"]);
+        assert_eq!(filtered_out, 1);
+    }
+
+    #[test]
+    fn ids_are_stable_and_unique() {
+        let json = generic_blueprint();
+        let mut dictionary = dictionary();
+        dictionary.color_property_names = vec!["TintColor".to_string(), "Color".to_string()];
+        let (first, _) = extract(&json, &dictionary);
+        let (second, _) = extract(&json, &dictionary);
+
+        let ids: Vec<_> = first.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            second.iter().map(|p| p.id.as_str()).collect::<Vec<_>>()
+        );
+        let mut unique = ids.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len());
+        for param in &first {
+            assert_eq!(
+                param.id,
+                format!("Effects/Hero/M_Test.json#{}", param.pointer)
+            );
+            assert_eq!(param.file_name, "M_Test.json");
+            assert_eq!(param.relative_path, "Effects/Hero/M_Test.json");
+        }
+    }
+
+    #[test]
+    fn sources_read_content_or_cached_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("M_Test.json");
+        fs::write(&json_path, material_instance().to_string()).unwrap();
+
+        let cached = ColorSource {
+            relative_path: "M_Test.json".to_string(),
+            json_path: Some(json_path.to_string_lossy().to_string()),
+            content: None,
+        };
+        assert_eq!(cached.read().unwrap(), material_instance());
+
+        let opened = ColorSource {
+            relative_path: "M_Test.json".to_string(),
+            json_path: None,
+            content: Some("{ \"Exports\": [".to_string()),
+        };
+        assert_eq!(opened.read().unwrap_err().code, ErrorCode::InvalidJson);
+
+        let missing = ColorSource {
+            relative_path: "Gone.json".to_string(),
+            json_path: Some(dir.path().join("Gone.json").to_string_lossy().to_string()),
+            content: None,
+        };
+        assert_eq!(missing.read().unwrap_err().code, ErrorCode::IoError);
+    }
+}
@@ -0,0 +1,137 @@
+//! Errors returned by Tauri commands and attached to per-file results.
+//!
+//! Serialized as `{ code, message, path?, tool_output? }` so the frontend can
+//! pick a remediation hint from `code` and keep raw UAssetCLI output out of
+//! the message.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::usmap::UsmapError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// UAssetCLI (or the dotnet host for a .dll) is missing.
+    ToolNotFound,
+    /// UAssetCLI started but crashed or broke its protocol.
+    ToolFailed,
+    /// A usmap is configured but the file is gone.
+    UsmapMissing,
+    UsmapInvalid,
+    AssetParseFailed,
+    IoError,
+    CacheCorrupt,
+    /// A JSON file that should hold a converted asset does not parse.
+    InvalidJson,
+    /// A setting value was rejected, e.g. an unknown engine version.
+    InvalidSetting,
+    /// A file is not a cache bundle, or one that was cut off.
+    InvalidBundle,
+    /// Another batch conversion is already running.
+    Busy,
+    /// `cancel_conversion` without a running batch.
+    NotRunning,
+    /// The batch was cancelled before this file was converted.
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// File the error is about, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// What UAssetCLI printed, e.g. a .NET stack trace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_output: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            path: None,
+            tool_output: None,
+        }
+    }
+
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_string_lossy().to_string());
+        self
+    }
+
+    pub fn with_tool_output(mut self, output: impl Into<String>) -> Self {
+        let output = output.into();
+        self.tool_output = Some(output).filter(|o| !o.trim().is_empty());
+        self
+    }
+
+    pub fn io(path: impl AsRef<Path>, error: impl fmt::Display) -> Self {
+        let path = path.as_ref();
+        Self::new(
+            ErrorCode::IoError,
+            format!("{}: {}", path.to_string_lossy(), error),
+        )
+        .with_path(path)
+    }
+
+    pub fn usmap(path: impl AsRef<Path>, error: UsmapError) -> Self {
+        let path = path.as_ref();
+        let code = match &error {
+            UsmapError::Io(_) if !path.exists() => ErrorCode::UsmapMissing,
+            UsmapError::Io(_) => ErrorCode::IoError,
+            _ => ErrorCode::UsmapInvalid,
+        };
+        Self::new(code, error.to_string()).with_path(path)
+    }
+
+    /// Classifies a failed UAssetCLI command from its exit code and the
+    /// `{ success: false, error, details }` object it printed.
+    pub fn from_tool(exit_code: i32, stdout: &str) -> Self {
+        let parsed = serde_json::from_str::<serde_json::Value>(stdout).ok();
+        let field = |name: &str| {
+            parsed
+                .as_ref()
+                .and_then(|v| v.get(name))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        let code = match exit_code {
+            2 => ErrorCode::IoError,
+            3 => ErrorCode::UsmapInvalid,
+            4 => ErrorCode::AssetParseFailed,
+            5 => ErrorCode::IoError,
+            _ => ErrorCode::ToolFailed,
+        };
+        let message =
+            field("error").unwrap_or_else(|| format!("UAssetCLI exited with code {}", exit_code));
+        let error = Self::new(code, message);
+        match field("details") {
+            Some(details) => error.with_tool_output(details),
+            None if parsed.is_none() => error.with_tool_output(stdout),
+            None => error,
+        }
+    }
+
+    /// Classifies the error string UAssetCLI reports for one file of a batch.
+    pub fn from_batch_file(message: &str, path: impl AsRef<Path>) -> Self {
+        let code = if message == "File not found" {
+            ErrorCode::IoError
+        } else {
+            ErrorCode::AssetParseFailed
+        };
+        Self::new(code, message).with_path(path)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
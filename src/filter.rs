@@ -0,0 +1,393 @@
+//! Parameter name filtering.
+//!
+//! A dictionary's `include_keywords` and `exclude_keywords` act as
+//! case-insensitive substring allow and deny rules at priority 0, and its
+//! typed `rules` add substring, glob, regex and exact matches at any
+//! priority. The highest-priority matching rule decides; `precedence`
+//! settles an allow and a deny that match at the same priority. A name no
+//! rule matches is kept only if there are no allow rules at all, like an
+//! empty include list always did.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, ErrorCode};
+use crate::FilterDictionary;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    Substring,
+    /// `*` and `?` wildcards against the whole name.
+    Glob,
+    /// Matches anywhere in the name unless anchored.
+    Regex,
+    Exact,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Allow,
+    Deny,
+}
+
+/// Which action wins when an allow and a deny rule match at the same
+/// priority.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Precedence {
+    #[default]
+    DenyOverAllow,
+    AllowOverDeny,
+}
+
+impl Precedence {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn winner(self) -> RuleAction {
+        match self {
+            Self::DenyOverAllow => RuleAction::Deny,
+            Self::AllowOverDeny => RuleAction::Allow,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct FilterRule {
+    pub kind: RuleKind,
+    pub pattern: String,
+    pub action: RuleAction,
+    /// Higher runs first. Keywords are priority 0.
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+/// Where the deciding rule came from: `include_keywords`,
+/// `exclude_keywords` or `rules`, and its index in that list.
+#[derive(Debug, Serialize, Clone)]
+pub struct DecidingRule {
+    pub source: &'static str,
+    pub index: usize,
+    #[serde(flatten)]
+    pub rule: FilterRule,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FilterDecision {
+    pub name: String,
+    pub matched: bool,
+    /// `None` when no rule matched and the default applied.
+    pub rule: Option<DecidingRule>,
+}
+
+enum Test {
+    Substring(String),
+    Exact(String),
+    Regex(Regex),
+}
+
+struct CompiledRule {
+    source: &'static str,
+    index: usize,
+    rule: FilterRule,
+    test: Test,
+}
+
+impl CompiledRule {
+    fn new(source: &'static str, index: usize, rule: FilterRule) -> Result<Self, AppError> {
+        let fold = |s: &str| {
+            if rule.case_sensitive {
+                s.to_string()
+            } else {
+                s.to_lowercase()
+            }
+        };
+        let regex = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(!rule.case_sensitive)
+                .build()
+                .map_err(|e| {
+                    AppError::new(
+                        ErrorCode::InvalidSetting,
+                        format!(
+                            "Filter rule {} (\"{}\") is invalid: {}",
+                            index + 1,
+                            rule.pattern,
+                            e
+                        ),
+                    )
+                })
+        };
+        let test = match rule.kind {
+            RuleKind::Substring => Test::Substring(fold(&rule.pattern)),
+            RuleKind::Exact => Test::Exact(fold(&rule.pattern)),
+            RuleKind::Glob => Test::Regex(regex(&glob_to_regex(&rule.pattern))?),
+            RuleKind::Regex => Test::Regex(regex(&rule.pattern)?),
+        };
+        Ok(Self {
+            source,
+            index,
+            rule,
+            test,
+        })
+    }
+
+    /// `folded` is `name` lowercased, shared by every case-insensitive rule.
+    fn matches(&self, name: &str, folded: &str) -> bool {
+        let subject = if self.rule.case_sensitive {
+            name
+        } else {
+            folded
+        };
+        match &self.test {
+            Test::Substring(pattern) => subject.contains(pattern.as_str()),
+            Test::Exact(pattern) => subject == pattern,
+            Test::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// A dictionary compiled for matching many names.
+pub struct FilterMatcher {
+    rules: Vec<CompiledRule>,
+    precedence: Precedence,
+    has_allow: bool,
+}
+
+impl FilterMatcher {
+    /// Fails if a regex rule does not compile.
+    pub fn new(dictionary: &FilterDictionary) -> Result<Self, AppError> {
+        fn keywords<'a>(
+            source: &'static str,
+            keywords: &'a [String],
+            action: RuleAction,
+        ) -> impl Iterator<Item = Result<CompiledRule, AppError>> + 'a {
+            keywords.iter().enumerate().map(move |(index, keyword)| {
+                let rule = FilterRule {
+                    kind: RuleKind::Substring,
+                    pattern: keyword.clone(),
+                    action,
+                    priority: 0,
+                    case_sensitive: false,
+                };
+                CompiledRule::new(source, index, rule)
+            })
+        }
+        let rules = keywords(
+            "include_keywords",
+            &dictionary.include_keywords,
+            RuleAction::Allow,
+        )
+        .chain(keywords(
+            "exclude_keywords",
+            &dictionary.exclude_keywords,
+            RuleAction::Deny,
+        ))
+        .chain(
+            dictionary
+                .rules
+                .iter()
+                .enumerate()
+                .map(|(index, rule)| CompiledRule::new("rules", index, rule.clone())),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            has_allow: rules.iter().any(|r| r.rule.action == RuleAction::Allow),
+            rules,
+            precedence: dictionary.precedence,
+        })
+    }
+
+    pub fn decide(&self, name: &str) -> FilterDecision {
+        let folded = name.to_lowercase();
+        let mut best: Option<&CompiledRule> = None;
+        for candidate in self.rules.iter().filter(|r| r.matches(name, &folded)) {
+            best = match best {
+                None => Some(candidate),
+                Some(current) if candidate.rule.priority > current.rule.priority => Some(candidate),
+                Some(current)
+                    if candidate.rule.priority == current.rule.priority
+                        && current.rule.action != self.precedence.winner()
+                        && candidate.rule.action == self.precedence.winner() =>
+                {
+                    Some(candidate)
+                }
+                keep => keep,
+            };
+        }
+
+        FilterDecision {
+            name: name.to_string(),
+            matched: best.map_or(!self.has_allow, |r| r.rule.action == RuleAction::Allow),
+            rule: best.map(|r| DecidingRule {
+                source: r.source,
+                index: r.index,
+                rule: r.rule.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: RuleKind, pattern: &str, action: RuleAction, priority: i32) -> FilterRule {
+        FilterRule {
+            kind,
+            pattern: pattern.to_string(),
+            action,
+            priority,
+            case_sensitive: false,
+        }
+    }
+
+    fn filter_dictionary(
+        include: &[&str],
+        exclude: &[&str],
+        rules: Vec<FilterRule>,
+    ) -> FilterDictionary {
+        FilterDictionary {
+            include_keywords: include.iter().map(|k| k.to_string()).collect(),
+            exclude_keywords: exclude.iter().map(|k| k.to_string()).collect(),
+            color_property_names: Vec::new(),
+            rules,
+            precedence: Precedence::default(),
+        }
+    }
+
+    fn matched(dictionary: &FilterDictionary, name: &str) -> bool {
+        FilterMatcher::new(dictionary).unwrap().decide(name).matched
+    }
+
+    #[test]
+    fn higher_priority_decides() {
+        let dictionary = filter_dictionary(
+            &["color"],
+            &["mask"],
+            vec![rule(RuleKind::Exact, "MaskColor", RuleAction::Allow, 10)],
+        );
+        let matcher = FilterMatcher::new(&dictionary).unwrap();
+
+        let decision = matcher.decide("MaskColor");
+        assert!(decision.matched);
+        let deciding = decision.rule.unwrap();
+        assert_eq!((deciding.source, deciding.index), ("rules", 0));
+
+        // Keywords alone: the deny wins the priority 0 tie
+        let decision = matcher.decide("MaskColor_Enemy");
+        assert!(!decision.matched);
+        assert_eq!(decision.rule.unwrap().source, "exclude_keywords");
+    }
+
+    #[test]
+    fn precedence_settles_equal_priority() {
+        let mut dictionary = filter_dictionary(
+            &[],
+            &[],
+            vec![
+                rule(RuleKind::Substring, "glow", RuleAction::Allow, 5),
+                rule(RuleKind::Substring, "tint", RuleAction::Deny, 5),
+            ],
+        );
+        assert!(!matched(&dictionary, "GlowTint"));
+
+        dictionary.precedence = Precedence::AllowOverDeny;
+        let decision = FilterMatcher::new(&dictionary).unwrap().decide("GlowTint");
+        assert!(decision.matched);
+        assert_eq!(decision.rule.unwrap().index, 0);
+    }
+
+    #[test]
+    fn globs_match_the_whole_name() {
+        let dictionary = filter_dictionary(
+            &[],
+            &[],
+            vec![
+                rule(RuleKind::Glob, "Color_?", RuleAction::Allow, 0),
+                rule(RuleKind::Glob, "Tint.*(1)", RuleAction::Allow, 0),
+            ],
+        );
+        assert!(matched(&dictionary, "Color_A"));
+        assert!(matched(&dictionary, "color_b"));
+        assert!(!matched(&dictionary, "Color_AB"));
+        assert!(!matched(&dictionary, "BaseColor_A"));
+
+        // Regex metacharacters in a glob are literal
+        assert!(matched(&dictionary, "Tint.Main(1)"));
+        assert!(!matched(&dictionary, "TintXMain1"));
+    }
+
+    #[test]
+    fn case_sensitivity() {
+        let mut sensitive = rule(RuleKind::Substring, "Emiss", RuleAction::Allow, 0);
+        sensitive.case_sensitive = true;
+        let dictionary = filter_dictionary(&[], &[], vec![sensitive]);
+        assert!(matched(&dictionary, "EmissiveColor"));
+        assert!(!matched(&dictionary, "emissivecolor"));
+
+        // Keywords never are
+        let dictionary = filter_dictionary(&["EMISS"], &[], Vec::new());
+        assert!(matched(&dictionary, "emissivecolor"));
+
+        let mut sensitive = rule(RuleKind::Regex, "^Tint", RuleAction::Allow, 0);
+        sensitive.case_sensitive = true;
+        let dictionary = filter_dictionary(&[], &[], vec![sensitive]);
+        assert!(matched(&dictionary, "TintColor"));
+        assert!(!matched(&dictionary, "tintcolor"));
+    }
+
+    #[test]
+    fn unmatched_names_default_to_the_allow_rules() {
+        // Without allow rules everything not denied is kept
+        let deny_only = filter_dictionary(&[], &["uv"], Vec::new());
+        let decision = FilterMatcher::new(&deny_only).unwrap().decide("BaseColor");
+        assert!(decision.matched);
+        assert!(decision.rule.is_none());
+        assert!(!matched(&deny_only, "UVOffset"));
+
+        let with_allow = filter_dictionary(
+            &[],
+            &["uv"],
+            vec![rule(RuleKind::Exact, "Tint", RuleAction::Allow, -1)],
+        );
+        assert!(!matched(&with_allow, "BaseColor"));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let dictionary = filter_dictionary(
+            &["color"],
+            &[],
+            vec![
+                rule(RuleKind::Regex, "^ok$", RuleAction::Allow, 0),
+                rule(RuleKind::Regex, "Tint(", RuleAction::Deny, 0),
+            ],
+        );
+        let error = FilterMatcher::new(&dictionary).err().unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidSetting);
+        assert!(error
+            .message
+            .starts_with("Filter rule 2 (\"Tint(\") is invalid"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Project-local settings.
+//!
+//! A `.rvfxe.json` in the opened root folder holds some of the settings
+//! fields, e.g. the usmap or filter profiles one mod needs. It is layered
+//! over the global `settings.json`: the fields it has win, and edits to them
+//! are saved back to it instead of leaking into every other mod.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, ErrorCode};
+use crate::{migrate, persist, save_settings, watch, AppSettings, FilterDictionary};
+
+pub const PROJECT_SETTINGS_FILE: &str = ".rvfxe.json";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingSource {
+    Global,
+    Project,
+}
+
+/// The effective settings and where each field came from.
+#[derive(Debug, Serialize, Clone)]
+pub struct LayeredSettings {
+    pub settings: AppSettings,
+    /// Settings file of the open root folder. It is only created once a
+    /// field is overridden.
+    pub project_file: Option<String>,
+    /// Source of every field, by its name in `settings`.
+    pub sources: BTreeMap<String, SettingSource>,
+}
+
+#[derive(Clone)]
+struct ProjectLayer {
+    path: PathBuf,
+    fields: Map<String, Value>,
+    /// Fields that did not parse. Ignored, but written back untouched.
+    unreadable: Map<String, Value>,
+}
+
+impl ProjectLayer {
+    fn load(root: &Path, global: &AppSettings) -> Result<Self, AppError> {
+        let path = root.join(PROJECT_SETTINGS_FILE);
+        let mut fields = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(Value::Object(fields)) => fields,
+                Ok(_) => return Err(invalid(&path, "not a JSON object".to_string())),
+                Err(e) => return Err(invalid(&path, e.to_string())),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Map::new(),
+            Err(e) => return Err(AppError::io(&path, e)),
+        };
+        fields.remove("version");
+
+        let (_, dropped) =
+            migrate::overlay(global, fields.clone()).map_err(|e| invalid(&path, e))?;
+        if !dropped.is_empty() {
+            eprintln!(
+                "[DEBUG] Ignoring unreadable project settings in {:?}: {}",
+                path,
+                dropped.join(", ")
+            );
+        }
+        let unreadable = dropped
+            .iter()
+            .filter_map(|key| fields.remove_entry(key))
+            .collect();
+        eprintln!(
+            "[DEBUG] Project settings {:?} override {} field(s)",
+            path,
+            fields.len()
+        );
+        Ok(Self {
+            path,
+            fields,
+            unreadable,
+        })
+    }
+
+    fn save(&self) -> Result<(), AppError> {
+        let mut file = Map::new();
+        file.insert(
+            "version".to_string(),
+            Value::from(migrate::SETTINGS_VERSION),
+        );
+        file.extend(self.unreadable.clone());
+        file.extend(self.fields.clone());
+        let json = serde_json::to_string_pretty(&file).map_err(|e| AppError::io(&self.path, e))?;
+        persist::write_atomic(&self.path, json.as_bytes())
+            .map_err(|e| AppError::io(&self.path, e))?;
+        watch::record_own_write(&self.path);
+        Ok(())
+    }
+}
+
+fn invalid(path: &Path, message: String) -> AppError {
+    AppError::new(
+        ErrorCode::InvalidSetting,
+        format!("Project settings are unreadable: {}", message),
+    )
+    .with_path(path)
+}
+
+fn to_fields(settings: &AppSettings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+#[derive(Clone)]
+pub struct SettingsLayers {
+    global: AppSettings,
+    project: Option<ProjectLayer>,
+    /// Linked filter dictionaries as last read from their files. They win
+    /// over the copies in the settings files without being written there.
+    linked: BTreeMap<String, FilterDictionary>,
+}
+
+impl SettingsLayers {
+    pub fn new(global: AppSettings) -> Self {
+        Self {
+            global,
+            project: None,
+            linked: BTreeMap::new(),
+        }
+    }
+
+    /// Layers `<root>/.rvfxe.json` over the global settings, or drops the
+    /// project layer for `None`. On error no project layer is left open.
+    pub fn open_project(&mut self, root: Option<&Path>) -> Result<(), AppError> {
+        self.project = None;
+        if let Some(root) = root {
+            self.project = Some(ProjectLayer::load(root, &self.global)?);
+        }
+        Ok(())
+    }
+
+    /// Reads the project file again, and takes `global` as the new global
+    /// settings if given.
+    pub fn reload(&mut self, global: Option<AppSettings>) -> Result<(), AppError> {
+        if let Some(global) = global {
+            self.global = global;
+        }
+        if let Some(path) = self.project_file() {
+            let root = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+            self.project = Some(ProjectLayer::load(&root, &self.global)?);
+        }
+        Ok(())
+    }
+
+    pub fn project_file(&self) -> Option<&Path> {
+        self.project.as_ref().map(|project| project.path.as_path())
+    }
+
+    /// Replaces the linked filter dictionaries, by profile name.
+    pub fn set_linked(&mut self, linked: BTreeMap<String, FilterDictionary>) {
+        self.linked = linked;
+    }
+
+    pub fn effective(&self) -> AppSettings {
+        let mut settings = match &self.project {
+            Some(project) => migrate::overlay(&self.global, project.fields.clone())
+                .map(|(settings, _)| settings)
+                .unwrap_or_else(|_| self.global.clone()),
+            None => self.global.clone(),
+        };
+        let profiles = &mut settings.filter_profiles;
+        for (name, dictionary) in &self.linked {
+            if profiles.links.contains_key(name) {
+                profiles.profiles.insert(name.clone(), dictionary.clone());
+            }
+        }
+        settings
+    }
+
+    pub fn describe(&self) -> LayeredSettings {
+        let settings = self.effective();
+        let sources = to_fields(&settings)
+            .into_iter()
+            .map(|(key, _)| {
+                let source = match &self.project {
+                    Some(project) if project.fields.contains_key(&key) => SettingSource::Project,
+                    _ => SettingSource::Global,
+                };
+                (key, source)
+            })
+            .collect();
+        LayeredSettings {
+            settings,
+            project_file: self
+                .project_file()
+                .map(|path| path.to_string_lossy().to_string()),
+            sources,
+        }
+    }
+
+    /// Saves every field that differs between `before` and `after` to the
+    /// layer it currently comes from.
+    pub fn save_changes(
+        &mut self,
+        before: &AppSettings,
+        after: &AppSettings,
+    ) -> Result<(), AppError> {
+        let before = to_fields(before);
+        let mut global = to_fields(&self.global);
+        let mut project = self.project.as_ref().map(|p| p.fields.clone());
+        let mut global_changed = false;
+        let mut project_changed = false;
+        for (key, value) in to_fields(after) {
+            if before.get(&key) == Some(&value) {
+                continue;
+            }
+            match &mut project {
+                Some(fields) if fields.contains_key(&key) => {
+                    fields.insert(key, value);
+                    project_changed = true;
+                }
+                _ => {
+                    global.insert(key, value);
+                    global_changed = true;
+                }
+            }
+        }
+
+        if global_changed {
+            let global = serde_json::from_value(Value::Object(global))
+                .map_err(|e| AppError::new(ErrorCode::InvalidSetting, e.to_string()))?;
+            save_settings(&global)?;
+            self.global = global;
+        }
+        if let (Some(layer), Some(fields), true) = (&mut self.project, project, project_changed) {
+            let previous = std::mem::replace(&mut layer.fields, fields);
+            if let Err(e) = layer.save() {
+                layer.fields = previous;
+                return Err(e);
+            }
+        }
+        // Edits to a linked dictionary now are its saved copy
+        let profiles = &after.filter_profiles.profiles;
+        self.linked.retain(|name, _| profiles.contains_key(name));
+        for (name, dictionary) in &mut self.linked {
+            dictionary.clone_from(&profiles[name]);
+        }
+        Ok(())
+    }
+
+    /// Moves `key` into the project layer with its current value, or back
+    /// out of it so the global value applies again.
+    pub fn set_override(&mut self, key: &str, enabled: bool) -> Result<(), AppError> {
+        let previous = self.project.as_ref().map(|project| project.fields.clone());
+        if self.change_override(key, enabled)? {
+            if let (Some(project), Some(previous)) = (&mut self.project, previous) {
+                if let Err(e) = project.save() {
+                    project.fields = previous;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `set_override` without saving, to see what it would change. Returns
+    /// whether the project layer changed.
+    pub fn change_override(&mut self, key: &str, enabled: bool) -> Result<bool, AppError> {
+        let effective = to_fields(&self.effective());
+        let Some(project) = &mut self.project else {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                "Open a root folder to override settings for it",
+            ));
+        };
+        let Some(value) = effective.get(key).filter(|_| key != "version") else {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                format!("Unknown setting: {}", key),
+            ));
+        };
+
+        Ok(if enabled {
+            project
+                .fields
+                .insert(key.to_string(), value.clone())
+                .is_none()
+        } else {
+            project.fields.remove(key).is_some()
+        })
+    }
+}
@@ -1,25 +1,61 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::future::Future;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
-use std::sync::Mutex;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
+use tokio::sync::oneshot;
+
+mod bundle;
+pub mod cli;
+mod colors;
+pub mod error;
+mod filter;
+mod layers;
+mod mappings;
+mod migrate;
+mod persist;
+mod profiles;
+mod repair;
+pub mod usmap;
+mod verify;
+mod watch;
+mod worker;
+
+use bundle::CacheBundleReport;
+use colors::{ColorExtraction, ColorSource, ExtractionFailure, ParamSummary};
+use error::{AppError, ErrorCode};
+use filter::{FilterDecision, FilterMatcher, FilterRule, Precedence};
+use layers::{LayeredSettings, SettingsLayers};
+use mappings::{MappingEntry, MappingImport, MappingInfo};
+use profiles::FilterProfiles;
+use repair::{CacheRepairReport, RepairInputs, CORRUPT_INDEX_NAME};
+use usmap::{Usmap, UsmapSummary};
+use verify::RoundTripReport;
+use worker::{WorkerOutput, WorkerPool};
 
 // ============================================================================
 // STATE & TYPES
 // ============================================================================
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", default)]
 pub struct FilterDictionary {
     pub include_keywords: Vec<String>,
     pub exclude_keywords: Vec<String>,
     pub color_property_names: Vec<String>,
+    /// Typed rules matched alongside the keywords, see `filter`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<FilterRule>,
+    #[serde(skip_serializing_if = "Precedence::is_default")]
+    pub precedence: Precedence,
 }
 
 impl Default for FilterDictionary {
@@ -53,6 +89,8 @@ impl Default for FilterDictionary {
                 "TextColor".to_string(),
                 "BackgroundColor".to_string(),
             ],
+            rules: Vec::new(),
+            precedence: Precedence::default(),
         }
     }
 }
@@ -60,21 +98,65 @@ impl Default for FilterDictionary {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
+    /// Schema version the file was written with, see `migrate`.
+    #[serde(default)]
+    pub version: u32,
     pub usmap_path: Option<String>,
+    /// Hash of the library mapping to convert with, see `mappings`. Takes
+    /// precedence over `usmap_path`.
+    #[serde(default)]
+    pub active_mapping: Option<String>,
     pub show_detailed_errors: bool,
     #[serde(default)]
     pub auto_clear_cache: bool,
+    /// Named filter dictionaries, see `profiles`.
+    #[serde(default)]
+    pub filter_profiles: FilterProfiles,
+    /// UAssetCLI to use instead of the auto-detected one.
     #[serde(default)]
-    pub filter_dictionary: FilterDictionary,
+    pub uasset_cli_path: Option<String>,
+    /// How many UAssetCLI processes a large batch is split across.
+    #[serde(default = "default_conversion_concurrency")]
+    pub conversion_concurrency: usize,
+    /// Read written .uasset files back and compare them with their JSON.
+    #[serde(default)]
+    pub verify_round_trip: bool,
+    /// `EngineVersion` name UAssetCLI reads .uasset files with.
+    #[serde(default = "default_engine_version")]
+    pub engine_version: String,
+    /// Cached JSON is evicted least-recently-used first once the cache grows
+    /// past this. `None` means unlimited.
+    #[serde(default)]
+    pub max_cache_bytes: Option<u64>,
+    /// Folder the `rvfxe-cache` directory is kept in. The OS temp dir when
+    /// unset.
+    #[serde(default)]
+    pub cache_location: Option<String>,
+}
+
+fn default_conversion_concurrency() -> usize {
+    1
+}
+
+fn default_engine_version() -> String {
+    "VER_UE5_3".to_string()
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            version: migrate::SETTINGS_VERSION,
             usmap_path: None,
+            active_mapping: None,
             show_detailed_errors: true,
             auto_clear_cache: false,
-            filter_dictionary: FilterDictionary::default(),
+            filter_profiles: FilterProfiles::default(),
+            uasset_cli_path: None,
+            conversion_concurrency: default_conversion_concurrency(),
+            verify_round_trip: false,
+            engine_version: default_engine_version(),
+            max_cache_bytes: None,
+            cache_location: None,
         }
     }
 }
@@ -85,11 +167,163 @@ pub struct CacheEntry {
     pub json_path: String,
     pub uasset_path: String,
     pub timestamp: String,
+    /// What the JSON was produced with. Empty for entries written before it
+    /// was recorded.
+    #[serde(default)]
+    pub key: CacheKey,
+    /// Last time the entry was served or written, RFC 3339. Falls back to
+    /// `timestamp` for entries written before it was recorded.
+    #[serde(default)]
+    pub last_access: Option<String>,
+    /// Size and mtime of the asset when `hash` was computed.
+    #[serde(default)]
+    pub stat: Option<FileStat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<CachedSummary>,
+}
+
+/// Color parameters of the cached JSON, valid while the JSON and the color
+/// property names it was searched with stay the same.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedSummary {
+    pub property_names_key: String,
+    pub json_stat: FileStat,
+    #[serde(flatten)]
+    pub summary: ParamSummary,
+}
+
+/// Cheap stand-in for the content hash: while size and mtime match, the
+/// indexed hash is reused instead of reading the asset again.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FileStat {
+    pub size: u64,
+    pub modified_nanos: u64,
+}
+
+impl FileStat {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            size: meta.len(),
+            modified_nanos: modified.as_nanos() as u64,
+        })
+    }
+}
+
+/// Content hash of an asset and the stat it was taken with.
+#[derive(Debug, Clone)]
+pub(crate) struct Fingerprint {
+    hash: String,
+    stat: Option<FileStat>,
+}
+
+/// Everything besides the asset itself that changes UAssetCLI's output.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// SHA-256 of the usmap, `None` when converting without one.
+    pub usmap_hash: Option<String>,
+    pub tool_version: Option<String>,
+    pub engine_version: Option<String>,
+}
+
+/// Why a cached JSON could not be reused.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheMiss {
+    /// The asset has never been converted.
+    NotCached,
+    /// The cached JSON file was deleted.
+    JsonMissing,
+    AssetChanged,
+    UsmapChanged,
+    ToolChanged,
+    EngineVersionChanged,
+    /// The asset could not be hashed.
+    AssetUnreadable,
+    /// Written by an editor version that did not record a cache key.
+    KeyMissing,
+}
+
+impl CacheEntry {
+    fn new(fingerprint: Fingerprint, json_path: &str, uasset_path: &str, key: &CacheKey) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            hash: fingerprint.hash,
+            json_path: json_path.to_string(),
+            uasset_path: uasset_path.to_string(),
+            timestamp: now.clone(),
+            key: key.clone(),
+            last_access: Some(now),
+            stat: fingerprint.stat,
+            summary: None,
+        }
+    }
+
+    fn last_used(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let stamp = self.last_access.as_deref().unwrap_or(&self.timestamp);
+        chrono::DateTime::parse_from_rfc3339(stamp)
+            .ok()
+            .map(|t| t.with_timezone(&chrono::Utc))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CacheIndex {
     pub entries: HashMap<String, CacheEntry>,
+    /// JSON from cache bundles by asset hash, not yet claimed by a local path.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub imported: HashMap<String, Vec<ImportedEntry>>,
+}
+
+/// Converted JSON for any asset with a given hash, see `bundle`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportedEntry {
+    pub file_name: String,
+    pub json_path: String,
+    pub key: CacheKey,
+    pub timestamp: String,
+}
+
+impl CacheIndex {
+    /// Takes entries from `other` that are new or were written more recently,
+    /// keeping the latest access time of either side.
+    fn merge(&mut self, other: CacheIndex) {
+        for (uasset_path, mut theirs) in other.entries {
+            let Some(ours) = self.entries.get_mut(&uasset_path) else {
+                self.entries.insert(uasset_path, theirs);
+                continue;
+            };
+            let written = |entry: &CacheEntry| {
+                chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+                    .ok()
+                    .map(|t| t.with_timezone(&chrono::Utc))
+            };
+            let last_access = ours.last_used().max(theirs.last_used());
+            if written(&theirs) > written(ours) {
+                theirs.last_access = last_access.map(|t| t.to_rfc3339());
+                *ours = theirs;
+            } else {
+                ours.last_access = last_access.map(|t| t.to_rfc3339());
+            }
+        }
+        for (hash, theirs) in other.imported {
+            let ours = self.imported.entry(hash).or_default();
+            for entry in theirs {
+                if !ours.iter().any(|e| e.json_path == entry.json_path) {
+                    ours.push(entry);
+                }
+            }
+        }
+    }
+
+    /// Bundle-imported JSON for content with `hash` converted under `key`.
+    fn find_imported(&self, hash: &str, key: &CacheKey) -> Option<&ImportedEntry> {
+        self.imported
+            .get(hash)?
+            .iter()
+            .find(|entry| entry.key == *key && Path::new(&entry.json_path).is_file())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -97,6 +331,37 @@ pub struct CacheInfo {
     pub file_count: usize,
     pub total_size_bytes: u64,
     pub cache_dir: String,
+    /// Size of the indexed JSON files, which eviction may remove.
+    pub evictable_bytes: u64,
+    pub max_cache_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheMoveReport {
+    pub cache_dir: String,
+    pub moved_files: usize,
+    pub moved_bytes: u64,
+    /// Index entries whose `json_path` was pointed at the new directory.
+    pub rewritten_entries: usize,
+}
+
+/// `EngineVersion` values the installed UAssetCLI accepts.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EngineVersionList {
+    pub versions: Vec<String>,
+    pub default: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConversionToolInfo {
+    pub path: String,
+    pub source: String,
+    pub found: bool,
+    /// "native" or "dotnet"
+    pub runtime: String,
+    pub runtime_version: Option<String>,
+    pub tool_version: Option<String>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -104,16 +369,25 @@ pub struct ConversionResult {
     pub success: bool,
     pub json_path: Option<String>,
     pub cached: bool,
-    pub error: Option<String>,
+    /// Why the cache could not be used, for to-json conversions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_miss: Option<CacheMiss>,
+    pub error: Option<AppError>,
+    /// Only set for written assets when round-trip verification is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_trip: Option<RoundTripReport>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BatchConversionResult {
     pub total: usize,
     pub succeeded: usize,
     pub failed: usize,
     pub cached_count: usize,
     pub results: Vec<SingleConversionResult>,
+    /// Set when the batch was stopped through `cancel_conversion`.
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -123,7 +397,19 @@ pub struct SingleConversionResult {
     pub uasset_path: String,
     pub json_path: Option<String>,
     pub cached: bool,
-    pub error: Option<String>,
+    /// Why the cache could not be used, for to-json conversions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_miss: Option<CacheMiss>,
+    pub error: Option<AppError>,
+    /// The batch was cancelled before UAssetCLI got to this file.
+    #[serde(default)]
+    pub aborted: bool,
+    /// Only set for written assets when round-trip verification is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_trip: Option<RoundTripReport>,
+    /// Color parameters of a converted or cached asset, for to-json batches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ParamSummary>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -136,20 +422,72 @@ pub struct ConversionProgress {
     pub file_name: String,
     pub cached: bool,
     pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_path: Option<String>,
+}
+
+/// Reply channel handed to a running batch by `cancel_conversion`.
+type CancelReply = oneshot::Sender<BatchConversionResult>;
+
+/// The batch conversion currently running. `cancel` is taken once a
+/// cancellation has been requested.
+pub struct ConversionControl {
+    cancel: Option<oneshot::Sender<CancelReply>>,
 }
 
 pub struct AppState {
+    /// Effective settings, see `layers`.
     pub settings: Mutex<AppSettings>,
+    pub layers: Mutex<SettingsLayers>,
     pub cache_index: Mutex<CacheIndex>,
+    pub conversion: Mutex<Option<ConversionControl>>,
+    /// Held for the whole request, so conversions queue up behind each other.
+    pub worker: tokio::sync::Mutex<WorkerPool>,
+    key_inputs: Mutex<KeyInputs>,
+    /// Held while settings are reloaded from disk, see `reload_settings`.
+    reload: Mutex<()>,
+}
+
+/// Parts of the cache key that are slow to work out, with the path and stat
+/// they were worked out from.
+#[derive(Default)]
+struct KeyInputs {
+    usmap: Option<(PathBuf, FileStat, String)>,
+    tool: Option<(PathBuf, FileStat, Option<String>)>,
 }
 
+impl AppState {
+    pub fn new(layers: SettingsLayers, cache_index: CacheIndex) -> Self {
+        Self {
+            settings: Mutex::new(layers.effective()),
+            layers: Mutex::new(layers),
+            cache_index: Mutex::new(cache_index),
+            conversion: Mutex::new(None),
+            worker: tokio::sync::Mutex::new(WorkerPool::default()),
+            key_inputs: Mutex::new(KeyInputs::default()),
+            reload: Mutex::new(()),
+        }
+    }
+
+    fn cache_dir(&self) -> PathBuf {
+        get_cache_dir(&self.settings.lock().unwrap())
+    }
+}
+
+/// Receives every progress update of a batch conversion. The GUI forwards them
+/// as `conversion-progress` events, the CLI prints them to stderr.
+pub type ProgressCallback = Arc<dyn Fn(&ConversionProgress) + Send + Sync>;
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
-fn get_cache_dir() -> PathBuf {
-    let temp_dir = std::env::temp_dir();
-    temp_dir.join("rvfxe-cache")
+fn get_cache_dir(settings: &AppSettings) -> PathBuf {
+    let location = match &settings.cache_location {
+        Some(location) => PathBuf::from(location),
+        None => std::env::temp_dir(),
+    };
+    location.join("rvfxe-cache")
 }
 
 fn get_settings_path() -> PathBuf {
@@ -159,8 +497,8 @@ fn get_settings_path() -> PathBuf {
         .join("settings.json")
 }
 
-fn get_cache_index_path() -> PathBuf {
-    get_cache_dir().join("index.json")
+fn get_cache_index_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("index.json")
 }
 
 fn load_settings() -> AppSettings {
@@ -168,14 +506,32 @@ fn load_settings() -> AppSettings {
     eprintln!("[DEBUG] Loading settings from: {:?}", path);
     if path.exists() {
         match fs::read_to_string(&path) {
-            Ok(s) => match serde_json::from_str::<AppSettings>(&s) {
-                Ok(mut settings) => {
-                    // Force detailed errors to true since toggle was removed
-                    settings.show_detailed_errors = true;
-                    settings
+            Ok(s) => match migrate::parse_settings(&s) {
+                Ok(loaded) => {
+                    if !loaded.dropped.is_empty() {
+                        eprintln!(
+                            "[DEBUG] Reset unreadable settings to defaults: {}",
+                            loaded.dropped.join(", ")
+                        );
+                        backup_settings(&path);
+                    }
+                    if let Some(version) = loaded.migrated_from {
+                        eprintln!(
+                            "[DEBUG] Migrated settings from version {} to {}",
+                            version,
+                            migrate::SETTINGS_VERSION
+                        );
+                    }
+                    if loaded.migrated_from.is_some() || !loaded.dropped.is_empty() {
+                        if let Err(e) = save_settings(&loaded.settings) {
+                            eprintln!("[DEBUG] Failed to save migrated settings: {}", e);
+                        }
+                    }
+                    loaded.settings
                 }
                 Err(e) => {
                     eprintln!("[DEBUG] Failed to parse settings: {}", e);
+                    backup_settings(&path);
                     AppSettings::default()
                 }
             },
@@ -194,59 +550,251 @@ fn load_settings() -> AppSettings {
     }
 }
 
-fn save_settings(settings: &AppSettings) -> Result<(), String> {
+fn backup_settings(path: &Path) {
+    match migrate::backup_settings(path) {
+        Ok(backup) => eprintln!("[DEBUG] Backed up settings to {:?}", backup),
+        Err(e) => eprintln!("[DEBUG] Failed to back up settings: {}", e),
+    }
+}
+
+/// Applies `change` to the effective settings and saves each field it
+/// changed to the layer that field comes from. Nothing changes if saving
+/// fails.
+fn update_settings(
+    state: &AppState,
+    change: impl FnOnce(&mut AppSettings),
+) -> Result<(), AppError> {
+    let mut settings = state.settings.lock().unwrap();
+    let mut edited = settings.clone();
+    change(&mut edited);
+    state
+        .layers
+        .lock()
+        .unwrap()
+        .save_changes(&settings, &edited)?;
+    *settings = edited;
+    Ok(())
+}
+
+/// Holds off conversions while settings switch to `next` if that moves the
+/// cache directory, since a running one would keep writing to the old
+/// directory behind the new index. `None` if the cache stays put.
+fn guard_cache_move<'a>(
+    state: &'a AppState,
+    old_dir: &Path,
+    next: &AppSettings,
+) -> Result<Option<ActiveConversion<'a>>, AppError> {
+    if get_cache_dir(next) == old_dir {
+        return Ok(None);
+    }
+    ActiveConversion::begin(state).map(Some).map_err(|_| {
+        AppError::new(
+            ErrorCode::Busy,
+            "These settings use another cache folder, which cannot change while a conversion is running",
+        )
+    })
+}
+
+/// Recomputes the effective settings after a layer changed, and switches to
+/// the cache index of the new cache directory if that moved with it.
+fn apply_settings_layers(state: &AppState) -> LayeredSettings {
+    let old_dir = state.cache_dir();
+    let layered = state.layers.lock().unwrap().describe();
+    *state.settings.lock().unwrap() = layered.settings.clone();
+    let new_dir = state.cache_dir();
+    if new_dir != old_dir {
+        *state.cache_index.lock().unwrap() = load_cache_index(&new_dir);
+    }
+    layered
+}
+
+/// Reads the global settings file again for a hot reload. Unlike at
+/// startup, a file with unreadable fields is rejected rather than partly
+/// reset to defaults. `None` if there is no file.
+fn read_settings_file(path: &Path) -> Result<Option<AppSettings>, AppError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::io(path, e)),
+    };
+    let invalid = |message: String| {
+        AppError::new(
+            ErrorCode::InvalidSetting,
+            format!("Settings were not reloaded: {}", message),
+        )
+        .with_path(path)
+    };
+    let loaded = migrate::parse_settings(&content).map_err(invalid)?;
+    if !loaded.dropped.is_empty() {
+        return Err(invalid(format!("unreadable {}", loaded.dropped.join(", "))));
+    }
+    Ok(Some(loaded.settings))
+}
+
+/// The files settings are read from: the global and project settings and
+/// every linked filter dictionary.
+fn watched_settings_files(state: &AppState) -> Vec<PathBuf> {
+    let mut files = vec![get_settings_path()];
+    files.extend(
+        state
+            .layers
+            .lock()
+            .unwrap()
+            .project_file()
+            .map(Path::to_path_buf),
+    );
+    files.extend(
+        state
+            .settings
+            .lock()
+            .unwrap()
+            .filter_profiles
+            .links
+            .values()
+            .map(PathBuf::from),
+    );
+    files
+}
+
+/// Re-reads every settings layer and linked filter dictionary after one of
+/// them changed on disk. Nothing is applied unless all of them are valid.
+/// Returns the new settings if they differ from the ones in use.
+fn reload_settings(state: &AppState) -> Result<Option<LayeredSettings>, AppError> {
+    let Ok(_reload) = state.reload.try_lock() else {
+        return Err(AppError::new(
+            ErrorCode::Busy,
+            "Settings are already being reloaded",
+        ));
+    };
+    let global = read_settings_file(&get_settings_path())?;
+    let old_dir = state.cache_dir();
+    let (before, _conversion) = {
+        let mut layers = state.layers.lock().unwrap();
+        let before = serde_json::to_value(layers.describe()).ok();
+        let mut next = layers.clone();
+        next.reload(global)?;
+
+        let mut linked = BTreeMap::new();
+        for (name, path) in &next.effective().filter_profiles.links {
+            linked.insert(name.clone(), profiles::read_profile(Path::new(path))?);
+        }
+        next.set_linked(linked);
+        let effective = next.effective();
+        effective.filter_profiles.validate()?;
+        let conversion = guard_cache_move(state, &old_dir, &effective)?;
+        *layers = next;
+        (before, conversion)
+    };
+
+    let layered = apply_settings_layers(state);
+    Ok((serde_json::to_value(&layered).ok() != before).then_some(layered))
+}
+
+/// Writes the global settings file.
+fn save_settings(settings: &AppSettings) -> Result<(), AppError> {
     let path = get_settings_path();
     eprintln!("[DEBUG] Saving settings to: {:?}", path);
     if let Some(parent) = path.parent() {
         if let Err(e) = fs::create_dir_all(parent) {
             eprintln!("[DEBUG] Failed to create settings dir: {}", e);
-            return Err(e.to_string());
+            return Err(AppError::io(parent, e));
         }
     }
     match serde_json::to_string_pretty(settings) {
         Ok(json) => {
-            if let Err(e) = fs::write(&path, json) {
+            let lock = persist::LockFile::acquire(&path.with_extension("lock"))
+                .map_err(|e| AppError::io(&path, e))?;
+            if let Ok(existing) = fs::read_to_string(&path) {
+                if let Err(message) = migrate::check_overwrite(&existing) {
+                    eprintln!("[DEBUG] Not overwriting settings: {}", message);
+                    return Err(AppError::new(ErrorCode::InvalidSetting, message).with_path(&path));
+                }
+            }
+            let written = persist::write_atomic(&path, json.as_bytes());
+            drop(lock);
+            if let Err(e) = written {
                 eprintln!("[DEBUG] Failed to write settings file: {}", e);
-                Err(e.to_string())
+                Err(AppError::io(&path, e))
             } else {
+                watch::record_own_write(&path);
                 Ok(())
             }
         }
         Err(e) => {
             eprintln!("[DEBUG] Failed to serialize settings: {}", e);
-            Err(e.to_string())
+            Err(AppError::io(&path, e))
         }
     }
 }
 
-fn load_cache_index() -> CacheIndex {
-    let path = get_cache_index_path();
-    if path.exists() {
-        fs::read_to_string(&path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default()
-    } else {
+fn load_cache_index(cache_dir: &Path) -> CacheIndex {
+    read_cache_index(cache_dir).unwrap_or_else(|e| {
+        eprintln!("[DEBUG] Starting with an empty cache index: {}", e);
+        // Keep the corrupt file out of the way of the next save, so
+        // `repair_cache` can still recover entries from it
+        if e.code == ErrorCode::CacheCorrupt {
+            let _ = fs::rename(
+                get_cache_index_path(cache_dir),
+                cache_dir.join(CORRUPT_INDEX_NAME),
+            );
+        }
         CacheIndex::default()
+    })
+}
+
+fn read_cache_index(cache_dir: &Path) -> Result<CacheIndex, AppError> {
+    let path = get_cache_index_path(cache_dir);
+    if !path.exists() {
+        return Ok(CacheIndex::default());
     }
+    let content = fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+    serde_json::from_str(&content).map_err(|e| {
+        AppError::new(
+            ErrorCode::CacheCorrupt,
+            format!("Cache index is corrupt: {}", e),
+        )
+        .with_path(&path)
+    })
 }
 
-fn save_cache_index(index: &CacheIndex) -> Result<(), String> {
-    let path = get_cache_index_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+/// Writes `index` to disk and returns what was written: `index` merged with
+/// entries another editor instance saved since this one last did.
+fn save_cache_index(cache_dir: &Path, mut index: CacheIndex) -> Result<CacheIndex, AppError> {
+    let path = get_cache_index_path(cache_dir);
+    let lock_path = cache_dir.join("index.lock");
+    let _lock = persist::LockFile::acquire(&lock_path).map_err(|e| AppError::io(&lock_path, e))?;
+
+    // Entries whose JSON is gone were evicted or cleared, not added elsewhere
+    let mut on_disk = read_cache_index(cache_dir).unwrap_or_default();
+    on_disk.entries.retain(|uasset_path, entry| {
+        index.entries.contains_key(uasset_path) || Path::new(&entry.json_path).is_file()
+    });
+    for imported in on_disk.imported.values_mut() {
+        imported.retain(|entry| Path::new(&entry.json_path).is_file());
     }
-    let json = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())
+    index.merge(on_disk);
+
+    let json = serde_json::to_string_pretty(&index).map_err(|e| AppError::io(&path, e))?;
+    persist::write_atomic(&path, json.as_bytes()).map_err(|e| AppError::io(&path, e))?;
+    Ok(index)
+}
+
+/// Saves the in-memory index. The mutex is only held to take a snapshot and
+/// to merge the saved result back, never across disk IO.
+fn persist_cache_index(state: &AppState) -> Result<(), AppError> {
+    let snapshot = state.cache_index.lock().unwrap().clone();
+    let saved = save_cache_index(&state.cache_dir(), snapshot)?;
+    state.cache_index.lock().unwrap().merge(saved);
+    Ok(())
 }
 
-fn compute_file_hash(path: &Path) -> Result<String, String> {
-    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+fn compute_file_hash(path: &Path) -> Result<String, AppError> {
+    let mut file = fs::File::open(path).map_err(|e| AppError::io(path, e))?;
     let mut hasher = Sha256::new();
     let mut buffer = [0u8; 8192];
 
     loop {
-        let bytes_read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        let bytes_read = file.read(&mut buffer).map_err(|e| AppError::io(path, e))?;
         if bytes_read == 0 {
             break;
         }
@@ -256,145 +804,1602 @@ fn compute_file_hash(path: &Path) -> Result<String, String> {
     Ok(hex::encode(hasher.finalize()))
 }
 
-fn get_uasset_tool_path(app: &AppHandle) -> PathBuf {
-    // In development, use UAssetCLI from UAssetAPI.Lightweight
-    // In production, use the bundled resource
-    let cwd = std::env::current_dir().unwrap_or_default();
-    eprintln!("[DEBUG] Current working directory: {:?}", cwd);
+/// Fingerprints `paths`, skipping assets that cannot be read. Hashes are
+/// reused from the index while size and mtime match; the rest are hashed in
+/// parallel on the blocking thread pool.
+async fn fingerprint_assets(state: &AppState, paths: &[String]) -> HashMap<String, Fingerprint> {
+    let mut fingerprints = HashMap::new();
+    let mut to_hash = Vec::new();
+    {
+        let cache_index = state.cache_index.lock().unwrap();
+        for path in paths {
+            let Some(stat) = FileStat::of(Path::new(path)) else {
+                continue;
+            };
+            match cache_index.entries.get(path) {
+                Some(entry) if entry.stat == Some(stat) => {
+                    let hash = entry.hash.clone();
+                    fingerprints.insert(
+                        path.clone(),
+                        Fingerprint {
+                            hash,
+                            stat: Some(stat),
+                        },
+                    );
+                }
+                _ => to_hash.push((path.clone(), stat)),
+            }
+        }
+    }
+    eprintln!(
+        "[DEBUG] Fingerprints: {} unchanged, {} to hash",
+        fingerprints.len(),
+        to_hash.len()
+    );
+    if to_hash.is_empty() {
+        return fingerprints;
+    }
 
-    let dev_path = cwd
-        .join("UAssetAPI.Lightweight")
-        .join("UAssetCLI")
-        .join("bin")
-        .join("Debug")
-        .join("net8.0")
-        .join("win-x64")
-        .join("UAssetCLI.exe");
+    let hashed = map_blocking(to_hash, |(path, stat)| {
+        let hash = compute_file_hash(Path::new(&path)).ok()?;
+        Some((
+            path,
+            Fingerprint {
+                hash,
+                stat: Some(stat),
+            },
+        ))
+    })
+    .await;
+    fingerprints.extend(hashed);
+    fingerprints
+}
 
+/// Runs `f` over `items` in parallel on the blocking thread pool, one chunk
+/// per core, keeping the `Some` results.
+async fn map_blocking<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> Option<R> + Clone + Send + 'static,
+{
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let chunk_size = items.len().div_ceil(threads);
+    let mut items = items.into_iter();
+    let mut tasks = Vec::new();
+    loop {
+        let chunk: Vec<T> = items.by_ref().take(chunk_size).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        let f = f.clone();
+        tasks.push(tauri::async_runtime::spawn_blocking(move || {
+            chunk.into_iter().filter_map(f).collect::<Vec<_>>()
+        }));
+    }
+
+    let mut results = Vec::new();
+    for output in join_all(tasks).await {
+        match output {
+            Ok(output) => results.extend(output),
+            Err(e) => eprintln!("[DEBUG] Blocking task failed: {}", e),
+        }
+    }
+    results
+}
+
+/// Fills in the parameter summary of every successful to-json result. Summaries
+/// are reused from the index while still valid; the rest are read from the
+/// JSON in parallel and stored with their entries.
+async fn attach_summaries(state: &AppState, results: &mut [SingleConversionResult]) {
+    let names = state
+        .settings
+        .lock()
+        .unwrap()
+        .filter_profiles
+        .active()
+        .color_property_names
+        .clone();
+    let names_key = hex::encode(Sha256::digest(names.join("\n").as_bytes()));
+
+    let candidates: Vec<_> = {
+        let cache_index = state.cache_index.lock().unwrap();
+        results
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.success)
+            .filter_map(|(i, r)| {
+                let json_path = r.json_path.clone()?;
+                let cached = cache_index
+                    .entries
+                    .get(&r.uasset_path)
+                    .filter(|entry| entry.json_path == json_path)
+                    .and_then(|entry| entry.summary.clone());
+                Some((i, json_path, cached))
+            })
+            .collect()
+    };
+
+    let mut to_read = Vec::new();
+    for (i, json_path, cached) in candidates {
+        let json_stat = FileStat::of(Path::new(&json_path));
+        match cached.filter(|c| c.property_names_key == names_key && Some(c.json_stat) == json_stat)
+        {
+            Some(cached) => results[i].summary = Some(cached.summary),
+            None => to_read.push((i, json_path)),
+        }
+    }
+    let reused = results.iter().filter(|r| r.summary.is_some()).count();
+
+    let names = Arc::new(names);
+    let read = map_blocking(to_read, move |(i, json_path)| {
+        // Stat first, so an edit while reading leaves the summary stale, not wrong
+        let json_stat = FileStat::of(Path::new(&json_path))?;
+        let content = fs::read(&json_path).ok()?;
+        let json: serde_json::Value = serde_json::from_slice(&content).ok()?;
+        Some((i, json_stat, colors::summarize_colors(&json, &names)))
+    })
+    .await;
     eprintln!(
-        "[DEBUG] Checking dev path: {:?} exists={}",
-        dev_path,
-        dev_path.exists()
+        "[DEBUG] Parameter summaries: {} reused, {} read",
+        reused,
+        read.len()
     );
 
-    if dev_path.exists() {
-        return dev_path;
+    let mut cache_index = state.cache_index.lock().unwrap();
+    for (i, json_stat, summary) in read {
+        let result = &mut results[i];
+        if let Some(entry) = cache_index
+            .entries
+            .get_mut(&result.uasset_path)
+            .filter(|entry| Some(&entry.json_path) == result.json_path.as_ref())
+        {
+            entry.summary = Some(CachedSummary {
+                property_names_key: names_key.clone(),
+                json_stat,
+                summary: summary.clone(),
+            });
+        }
+        result.summary = Some(summary);
+    }
+}
+
+/// Builds the cache key for conversions with the current settings and tool.
+async fn current_cache_key(
+    state: &AppState,
+    tool_path: &Path,
+    options: &ToolOptions,
+) -> Result<CacheKey, AppError> {
+    let usmap_hash = match &options.usmap_path {
+        Some(path) => Some(usmap_hash(state, Path::new(path)).await?),
+        None => None,
+    };
+    Ok(CacheKey {
+        usmap_hash,
+        tool_version: tool_version(state, tool_path).await?,
+        engine_version: Some(options.engine_version.clone()),
+    })
+}
+
+/// SHA-256 of the usmap, hashed again only when its size or mtime changes.
+async fn usmap_hash(state: &AppState, path: &Path) -> Result<String, AppError> {
+    let stat = FileStat::of(path);
+    if let Some((known_path, known_stat, hash)) = &state.key_inputs.lock().unwrap().usmap {
+        if known_path == path && stat == Some(*known_stat) {
+            return Ok(hash.clone());
+        }
+    }
+    let owned = path.to_path_buf();
+    let hash = tauri::async_runtime::spawn_blocking(move || compute_file_hash(&owned))
+        .await
+        .map_err(|e| AppError::new(ErrorCode::IoError, format!("Hashing failed: {}", e)))??;
+    if let Some(stat) = stat {
+        state.key_inputs.lock().unwrap().usmap = Some((path.to_path_buf(), stat, hash.clone()));
+    }
+    Ok(hash)
+}
+
+/// The version UAssetCLI reports, asked again only when the tool changes.
+/// Without a working tool the last known version is used, so assets that are
+/// all cached still load.
+async fn tool_version(state: &AppState, tool_path: &Path) -> Result<Option<String>, AppError> {
+    let stat = FileStat::of(tool_path);
+    if let Some((known_path, known_stat, version)) = &state.key_inputs.lock().unwrap().tool {
+        if known_path == tool_path && stat == Some(*known_stat) {
+            return Ok(version.clone());
+        }
+    }
+    let output = match state
+        .worker
+        .lock()
+        .await
+        .primary()
+        .request(tool_path, &["version".to_string()], Arc::new(|_| {}))
+        .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            return match known_tool_version(state) {
+                Some(version) => {
+                    eprintln!(
+                        "[DEBUG] UAssetCLI unavailable ({}), keying the cache by {:?}",
+                        e, version
+                    );
+                    Ok(version)
+                }
+                None => Err(e),
+            };
+        }
+    };
+    let tool_version = parse_tool_version(&output.stdout);
+    if let Some(stat) = stat {
+        state.key_inputs.lock().unwrap().tool =
+            Some((tool_path.to_path_buf(), stat, tool_version.clone()));
+    }
+    Ok(tool_version)
+}
+
+/// The version in the output of `UAssetCLI version`, as cache keys record it.
+fn parse_tool_version(stdout: &str) -> Option<String> {
+    let version = serde_json::from_str::<serde_json::Value>(stdout).ok();
+    let field = |name: &str| {
+        version
+            .as_ref()
+            .and_then(|v| v.get(name)?.as_str().map(str::to_string))
+    };
+    // Builds from before cliVersion existed only report the UAssetAPI version
+    match (field("cliVersion"), field("uassetApiVersion")) {
+        (Some(cli), Some(api)) => Some(format!("{} (UAssetAPI {})", cli, api)),
+        (cli, api) => cli.or(api),
+    }
+}
+
+/// The tool version asked last this session, or else the one the most
+/// recently written cache entry was converted with.
+fn known_tool_version(state: &AppState) -> Option<Option<String>> {
+    if let Some((_, _, version)) = &state.key_inputs.lock().unwrap().tool {
+        return Some(version.clone());
+    }
+    state
+        .cache_index
+        .lock()
+        .unwrap()
+        .entries
+        .values()
+        .filter(|entry| entry.key != CacheKey::default())
+        .max_by(|a, b| a.timestamp.cmp(&b.timestamp))
+        .map(|entry| entry.key.tool_version.clone())
+}
+
+/// Returns the cached JSON for `uasset_path` if it was produced from the same
+/// asset with the same usmap, tool and engine version.
+fn lookup_cache<'a>(
+    index: &'a CacheIndex,
+    uasset_path: &str,
+    hash: &str,
+    key: &CacheKey,
+) -> Result<&'a CacheEntry, CacheMiss> {
+    let entry = index.entries.get(uasset_path).ok_or(CacheMiss::NotCached)?;
+    if entry.hash != hash {
+        Err(CacheMiss::AssetChanged)
+    } else if entry.key == CacheKey::default() {
+        Err(CacheMiss::KeyMissing)
+    } else if entry.key.usmap_hash != key.usmap_hash {
+        Err(CacheMiss::UsmapChanged)
+    } else if entry.key.tool_version != key.tool_version {
+        Err(CacheMiss::ToolChanged)
+    } else if entry.key.engine_version != key.engine_version {
+        Err(CacheMiss::EngineVersionChanged)
+    } else if !Path::new(&entry.json_path).exists() {
+        Err(CacheMiss::JsonMissing)
+    } else {
+        Ok(entry)
+    }
+}
+
+/// Copies the index entries of `uasset_paths` and the imported JSON for
+/// `fingerprints`, enough to look them up without holding the lock.
+fn index_snapshot<'a>(
+    state: &AppState,
+    uasset_paths: &[String],
+    fingerprints: impl Iterator<Item = &'a Fingerprint>,
+) -> CacheIndex {
+    let cache_index = state.cache_index.lock().unwrap();
+    let entries = uasset_paths
+        .iter()
+        .filter_map(|path| Some((path.clone(), cache_index.entries.get(path)?.clone())))
+        .collect();
+    let imported = fingerprints
+        .filter_map(|f| Some((f.hash.clone(), cache_index.imported.get(&f.hash)?.clone())))
+        .collect();
+    CacheIndex { entries, imported }
+}
+
+/// Like `lookup_cache`, but falls back to bundle-imported JSON of the same
+/// content. Returns the JSON path and, for an import, the entry adopting it
+/// for `uasset_path`.
+fn lookup_or_import(
+    index: &CacheIndex,
+    uasset_path: &str,
+    fingerprint: &Fingerprint,
+    key: &CacheKey,
+) -> Result<(String, Option<CacheEntry>), CacheMiss> {
+    match lookup_cache(index, uasset_path, &fingerprint.hash, key) {
+        Ok(entry) => Ok((entry.json_path.clone(), None)),
+        Err(miss) => {
+            let imported = index.find_imported(&fingerprint.hash, key).ok_or(miss)?;
+            let adopted =
+                CacheEntry::new(fingerprint.clone(), &imported.json_path, uasset_path, key);
+            Ok((imported.json_path.clone(), Some(adopted)))
+        }
+    }
+}
+
+/// Points at a UAssetCLI build and overrides every other location.
+const UASSET_CLI_ENV: &str = "RVFXE_UASSET_CLI";
+
+/// Runtime identifier `dotnet build -r` uses for this platform.
+const PLATFORM_RID: &str = if cfg!(target_os = "windows") {
+    "win-x64"
+} else if cfg!(target_os = "macos") {
+    if cfg!(target_arch = "aarch64") {
+        "osx-arm64"
+    } else {
+        "osx-x64"
+    }
+} else if cfg!(target_arch = "aarch64") {
+    "linux-arm64"
+} else {
+    "linux-x64"
+};
+
+/// A resolved UAssetCLI and where it came from: "env", "settings",
+/// "development", "release", "dist" or "bundled".
+#[derive(Debug, Clone)]
+pub struct ToolLocation {
+    pub path: PathBuf,
+    pub source: &'static str,
+}
+
+fn get_uasset_tool_path(app: &AppHandle) -> PathBuf {
+    locate_uasset_tool(app).path
+}
+
+fn locate_uasset_tool(app: &AppHandle) -> ToolLocation {
+    let configured = {
+        let state = app.state::<AppState>();
+        let settings = state.settings.lock().unwrap();
+        settings.uasset_cli_path.clone()
+    };
+    find_uasset_tool(app.path().resource_dir().ok(), configured)
+}
+
+/// Resolves UAssetCLI without an `AppHandle`. `resource_dir` is only known
+/// when running inside Tauri; headless callers pass `None`.
+fn find_uasset_tool(resource_dir: Option<PathBuf>, configured: Option<String>) -> ToolLocation {
+    // Explicit overrides win even when missing, so errors name the right path
+    if let Some(path) = std::env::var_os(UASSET_CLI_ENV).filter(|p| !p.is_empty()) {
+        eprintln!("[DEBUG] Using {}: {:?}", UASSET_CLI_ENV, path);
+        return ToolLocation {
+            path: PathBuf::from(path),
+            source: "env",
+        };
+    }
+    if let Some(path) = configured.filter(|p| !p.is_empty()) {
+        eprintln!("[DEBUG] Using UAssetCLI from settings: {:?}", path);
+        return ToolLocation {
+            path: PathBuf::from(path),
+            source: "settings",
+        };
     }
 
-    // Try release build
-    let release_path = cwd
+    // In development, use UAssetCLI from UAssetAPI.Lightweight
+    // In production, use the bundled resource
+    let cwd = std::env::current_dir().unwrap_or_default();
+    eprintln!("[DEBUG] Current working directory: {:?}", cwd);
+
+    let bin_dir = cwd
         .join("UAssetAPI.Lightweight")
         .join("UAssetCLI")
-        .join("bin")
-        .join("Release")
-        .join("net8.0")
-        .join("win-x64")
-        .join("publish")
-        .join("UAssetCLI.exe");
-
-    eprintln!(
-        "[DEBUG] Checking release path: {:?} exists={}",
-        release_path,
-        release_path.exists()
-    );
+        .join("bin");
+    // The project defaults to win-x64; its UAssetCLI.dll still runs elsewhere via dotnet
+    let mut rids = vec![PLATFORM_RID];
+    if PLATFORM_RID != "win-x64" {
+        rids.push("win-x64");
+    }
+    for (config, source) in [("Debug", "development"), ("Release", "release")] {
+        let framework_dir = bin_dir.join(config).join("net8.0");
+        let mut dirs = Vec::new();
+        for rid in &rids {
+            dirs.push(framework_dir.join(rid));
+            dirs.push(framework_dir.join(rid).join("publish"));
+        }
+        dirs.push(framework_dir);
 
-    if release_path.exists() {
-        return release_path;
+        for dir in dirs {
+            if let Some(path) = find_tool_in(&dir) {
+                return ToolLocation { path, source };
+            }
+        }
     }
 
     // Try tools folder next to executable (for organized distributions)
     if let Ok(exe_path) = std::env::current_exe() {
         if let Some(exe_dir) = exe_path.parent() {
-            let dist_path = exe_dir.join("tools").join("UAssetCLI.exe");
-            eprintln!(
-                "[DEBUG] Checking dist path (tools): {:?} exists={}",
-                dist_path,
-                dist_path.exists()
-            );
-            if dist_path.exists() {
-                return dist_path;
-            }
-
             // Also check directly next to exe (cargo-dist flattens includes)
-            let flat_path = exe_dir.join("UAssetCLI.exe");
-            eprintln!(
-                "[DEBUG] Checking dist path (flat): {:?} exists={}",
-                flat_path,
-                flat_path.exists()
-            );
-            if flat_path.exists() {
-                return flat_path;
+            for dir in [exe_dir.join("tools"), exe_dir.to_path_buf()] {
+                if let Some(path) = find_tool_in(&dir) {
+                    return ToolLocation {
+                        path,
+                        source: "dist",
+                    };
+                }
             }
         }
     }
 
     // Bundled resource path (in tools subfolder) - for Tauri builds
-    let bundled_path = app
-        .path()
-        .resource_dir()
-        .unwrap_or_default()
-        .join("tools")
-        .join("UAssetCLI.exe");
+    let bundled_dir = resource_dir.unwrap_or_default().join("tools");
+    let path = find_tool_in(&bundled_dir).unwrap_or_else(|| {
+        let fallback = bundled_dir.join(tool_file_names()[0]);
+        eprintln!("[DEBUG] Falling back to bundled path: {:?}", fallback);
+        fallback
+    });
+    ToolLocation {
+        path,
+        source: "bundled",
+    }
+}
+
+/// Native launcher first, then the framework-dependent assembly.
+fn tool_file_names() -> [&'static str; 2] {
+    if cfg!(target_os = "windows") {
+        ["UAssetCLI.exe", "UAssetCLI.dll"]
+    } else {
+        ["UAssetCLI", "UAssetCLI.dll"]
+    }
+}
+
+fn find_tool_in(dir: &Path) -> Option<PathBuf> {
+    tool_file_names()
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| {
+            let exists = path.is_file();
+            eprintln!("[DEBUG] Checking tool path: {:?} exists={}", path, exists);
+            exists
+        })
+}
+
+fn is_dotnet_assembly(tool_path: &Path) -> bool {
+    tool_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"))
+}
+
+/// Builds the command that launches UAssetCLI. A `UAssetCLI.dll` is run
+/// through the `dotnet` host.
+fn tool_command(tool_path: &Path) -> Command {
+    if is_dotnet_assembly(tool_path) {
+        let mut cmd = hidden_command("dotnet");
+        cmd.arg(tool_path);
+        cmd
+    } else {
+        hidden_command(tool_path)
+    }
+}
+
+/// A command that does not flash a console window on Windows.
+fn hidden_command(program: impl AsRef<std::ffi::OsStr>) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+/// Settings every UAssetCLI conversion is run with.
+struct ToolOptions {
+    usmap_path: Option<String>,
+    engine_version: String,
+}
+
+/// Reads the conversion settings. A configured usmap that has gone missing is
+/// an error: UAssetCLI would silently convert without mappings.
+fn tool_options(state: &AppState) -> Result<ToolOptions, AppError> {
+    let settings = state.settings.lock().unwrap();
+    let usmap_path = match &settings.active_mapping {
+        Some(hash) => {
+            let path = mappings::stored_path(hash);
+            if !path.is_file() {
+                return Err(AppError::new(
+                    ErrorCode::UsmapMissing,
+                    format!("Mapping {} is not in the mappings library", hash),
+                )
+                .with_path(&path));
+            }
+            Some(path.to_string_lossy().to_string())
+        }
+        None => settings.usmap_path.clone(),
+    };
+    if let Some(path) = usmap_path.as_ref().filter(|p| !Path::new(p).is_file()) {
+        return Err(AppError::new(
+            ErrorCode::UsmapMissing,
+            format!("Usmap file not found: {}", path),
+        )
+        .with_path(path));
+    }
+    Ok(ToolOptions {
+        usmap_path,
+        engine_version: settings.engine_version.clone(),
+    })
+}
+
+/// Forwards batch progress to the webview as `conversion-progress` events.
+fn progress_emitter(app: &AppHandle) -> ProgressCallback {
+    let app = app.clone();
+    Arc::new(move |progress| {
+        let _ = app.emit("conversion-progress", progress);
+    })
+}
+
+/// Marks a batch conversion as running for as long as it is alive, so
+/// `cancel_conversion` can reach it.
+struct ActiveConversion<'a> {
+    state: &'a AppState,
+    cancel: oneshot::Receiver<CancelReply>,
+}
+
+impl<'a> ActiveConversion<'a> {
+    fn begin(state: &'a AppState) -> Result<Self, AppError> {
+        let mut conversion = state.conversion.lock().unwrap();
+        if conversion.is_some() {
+            return Err(AppError::new(
+                ErrorCode::Busy,
+                "Another conversion is already running",
+            ));
+        }
+        let (cancel_tx, cancel) = oneshot::channel();
+        *conversion = Some(ConversionControl {
+            cancel: Some(cancel_tx),
+        });
+        Ok(Self { state, cancel })
+    }
+}
+
+impl Drop for ActiveConversion<'_> {
+    fn drop(&mut self) {
+        *self.state.conversion.lock().unwrap() = None;
+    }
+}
+
+/// One line of a UAssetCLI batch list and the file it is expected to write.
+struct BatchItem {
+    file_name: String,
+    input_path: String,
+    output_path: PathBuf,
+    /// The line as written to the list file.
+    list_line: String,
+    /// Why a to-json item is being converted rather than served from cache.
+    cache_miss: Option<CacheMiss>,
+}
+
+/// What UAssetCLI printed for one shard of a batch.
+struct ShardOutput {
+    stdout: String,
+    exit_code: i32,
+    progress: Vec<ConversionProgress>,
+}
+
+enum BatchOutcome {
+    /// One output per shard, in shard order.
+    Completed(Vec<ShardOutput>),
+    Cancelled {
+        progress: Vec<ConversionProgress>,
+        started: SystemTime,
+        reply: CancelReply,
+    },
+}
+
+/// Smaller lists are not worth starting another .NET process for.
+const MIN_SHARD_FILES: usize = 16;
+
+/// Each worker holds its own copy of the usmap, so this is capped well below
+/// what a many-core machine could run.
+const MAX_CONVERSION_CONCURRENCY: usize = 16;
+
+/// Splits `items` into at most `concurrency` shards of similar total file
+/// size. Biggest files are placed first, so a few huge assets do not all end
+/// up in the same process. `concurrency` may come from a hand-edited
+/// settings file or `--jobs`, so it is capped here too.
+fn shard_items(items: Vec<BatchItem>, concurrency: usize) -> Vec<Vec<BatchItem>> {
+    let count = concurrency
+        .min(MAX_CONVERSION_CONCURRENCY)
+        .clamp(1, items.len().div_ceil(MIN_SHARD_FILES).max(1));
+    if count == 1 {
+        return vec![items];
+    }
+
+    let mut sized: Vec<(u64, BatchItem)> = items
+        .into_iter()
+        .map(|item| {
+            let size = fs::metadata(&item.input_path).map_or(0, |m| m.len());
+            (size, item)
+        })
+        .collect();
+    sized.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+
+    let mut shards: Vec<(u64, Vec<BatchItem>)> = (0..count).map(|_| (0, Vec::new())).collect();
+    for (size, item) in sized {
+        let (shard_size, shard) = shards.iter_mut().min_by_key(|(s, _)| *s).unwrap();
+        *shard_size += size;
+        shard.push(item);
+    }
+    shards.into_iter().map(|(_, shard)| shard).collect()
+}
+
+/// Writes one UAssetCLI list file per shard, named `<name>_<index>.txt`.
+fn write_batch_lists(
+    dir: &Path,
+    name: &str,
+    shards: &[Vec<BatchItem>],
+) -> Result<Vec<PathBuf>, AppError> {
+    shards
+        .iter()
+        .enumerate()
+        .map(|(i, shard)| {
+            let path = dir.join(format!("{}_{}.txt", name, i));
+            let lines: Vec<&str> = shard.iter().map(|item| item.list_line.as_str()).collect();
+            fs::write(&path, lines.join("\n")).map_err(|e| AppError::io(&path, e))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Renumbers progress from several shards into one stream that counts up
+/// from `base` towards the batch `total` in the order updates arrive.
+fn merged_progress(on_progress: ProgressCallback, base: usize, total: usize) -> ProgressCallback {
+    let current = Mutex::new(base);
+    Arc::new(move |progress| {
+        let mut current = current.lock().unwrap();
+        *current += 1;
+        on_progress(&ConversionProgress {
+            current: *current,
+            total,
+            ..progress.clone()
+        });
+    })
+}
+
+/// Polls all `futures` on the current task and returns their outputs in order.
+async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+    std::future::poll_fn(|cx| {
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_none() {
+                if let Poll::Ready(value) = future.as_mut().poll(cx) {
+                    *output = Some(value);
+                }
+            }
+        }
+        if outputs.iter().all(Option::is_some) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+    outputs.into_iter().map(Option::unwrap).collect()
+}
+
+/// Runs one UAssetCLI batch command per entry of `shard_args`, each in its
+/// own worker, until all of them finish or the conversion is cancelled.
+/// Cancelling kills the workers; the next request starts fresh ones.
+async fn run_batch_tool(
+    state: &AppState,
+    tool_path: &Path,
+    shard_args: &[Vec<String>],
+    conversion: &mut ActiveConversion<'_>,
+    on_progress: ProgressCallback,
+) -> Result<BatchOutcome, AppError> {
+    let mut pool = state.worker.lock().await;
+    let workers = pool.workers(shard_args.len());
+    let starts = join_all(workers.iter_mut().map(|w| w.start(tool_path)).collect()).await;
+    if let Some(e) = starts.into_iter().find_map(Result::err) {
+        pool.release_extra();
+        return Err(e);
+    }
+    if workers.len() > 1 {
+        eprintln!("[DEBUG] Running batch across {} workers", workers.len());
+    }
+    let started = SystemTime::now();
+
+    // Wait for every shard to answer unless a cancel request arrives first
+    let race = {
+        let requests = workers
+            .iter_mut()
+            .zip(shard_args)
+            .map(|(worker, args)| worker.request(tool_path, args, on_progress.clone()))
+            .collect();
+        let mut requests = std::pin::pin!(join_all(requests));
+        let cancel = &mut conversion.cancel;
+        std::future::poll_fn(
+            |cx| -> Poll<Result<Vec<Result<WorkerOutput, AppError>>, CancelReply>> {
+                if let Poll::Ready(Ok(reply)) = Pin::new(&mut *cancel).poll(cx) {
+                    return Poll::Ready(Err(reply));
+                }
+                requests.as_mut().poll(cx).map(Ok)
+            },
+        )
+        .await
+    };
+
+    let outcome = match race {
+        Ok(results) => {
+            let mut outputs = Vec::with_capacity(results.len());
+            for (worker, result) in workers.iter_mut().zip(results) {
+                outputs.push(match result {
+                    Ok(output) => {
+                        if output.exit_code != 0 {
+                            eprintln!("[DEBUG] Exit code: {}", output.exit_code);
+                        }
+                        ShardOutput {
+                            stdout: output.stdout,
+                            exit_code: output.exit_code,
+                            progress: output.progress,
+                        }
+                    }
+                    Err(e) => {
+                        // Same as a one-shot run dying: report what the progress lines show
+                        eprintln!("[DEBUG] Batch failed: {}", e);
+                        ShardOutput {
+                            stdout: String::new(),
+                            exit_code: -1,
+                            progress: worker.abort().await,
+                        }
+                    }
+                });
+            }
+            BatchOutcome::Completed(outputs)
+        }
+        Err(reply) => {
+            eprintln!("[DEBUG] Cancelling batch conversion");
+            let mut progress = Vec::new();
+            for worker in workers.iter_mut() {
+                progress.extend(worker.abort().await);
+            }
+            BatchOutcome::Cancelled {
+                progress,
+                started,
+                reply,
+            }
+        }
+    };
+    pool.release_extra();
+    Ok(outcome)
+}
+
+/// Builds a UAssetCLI command line, adding the configured usmap and engine
+/// version.
+fn tool_args(command: &str, paths: &[&Path], options: &ToolOptions) -> Vec<String> {
+    let mut args = vec![command.to_string()];
+    args.extend(paths.iter().map(|p| p.to_string_lossy().to_string()));
+    if let Some(usmap) = &options.usmap_path {
+        args.push("--usmap".to_string());
+        args.push(usmap.to_string());
+    }
+    args.push("--engine".to_string());
+    args.push(options.engine_version.clone());
+    args
+}
+
+/// Builds per-file results for a cancelled batch. Files UAssetCLI never
+/// reported are marked aborted and whatever they wrote during this run is
+/// deleted.
+fn cancelled_results(
+    items: &[BatchItem],
+    progress: &[ConversionProgress],
+    started: SystemTime,
+    to_json: bool,
+) -> Vec<SingleConversionResult> {
+    let reports = match_progress(items, progress);
+    let mut results = Vec::with_capacity(items.len());
+
+    for (item, report) in items.iter().zip(reports) {
+        let output = item.output_path.to_string_lossy().to_string();
+        let (uasset_path, json_path) = if to_json {
+            (item.input_path.clone(), output)
+        } else {
+            (output, item.input_path.clone())
+        };
+
+        let (success, error, aborted) = match report {
+            Some(p) => (
+                p.error.is_none(),
+                progress_error(p, &item.input_path),
+                false,
+            ),
+            None => {
+                remove_partial_output(&item.output_path, started);
+                let error =
+                    AppError::new(ErrorCode::Cancelled, "Aborted").with_path(&item.input_path);
+                (false, Some(error), true)
+            }
+        };
+
+        results.push(SingleConversionResult {
+            success,
+            file_name: item.file_name.clone(),
+            uasset_path,
+            // The JSON is the input of a from-json run, so it is always known
+            json_path: (success || !to_json).then_some(json_path),
+            cached: false,
+            cache_miss: item.cache_miss,
+            error,
+            aborted,
+            round_trip: None,
+            summary: None,
+        });
+    }
+
+    results
+}
+
+fn progress_error(progress: &ConversionProgress, input_path: &str) -> Option<AppError> {
+    progress
+        .error
+        .as_deref()
+        .map(|e| AppError::from_batch_file(e, input_path))
+}
+
+/// Pairs each item with the progress line UAssetCLI printed for it, if any.
+fn match_progress<'a>(
+    items: &[BatchItem],
+    progress: &'a [ConversionProgress],
+) -> Vec<Option<&'a ConversionProgress>> {
+    let mut used = vec![false; progress.len()];
+    items
+        .iter()
+        .map(|item| {
+            // Older UAssetCLI builds only report the file name
+            let i = progress.iter().enumerate().position(|(i, p)| {
+                !used[i]
+                    && match &p.input_path {
+                        Some(input) => *input == item.input_path,
+                        None => p.file_name == item.file_name,
+                    }
+            })?;
+            used[i] = true;
+            Some(&progress[i])
+        })
+        .collect()
+}
+
+/// Builds per-file results of a finished batch run. Records from the stdout
+/// summary win; files missing from it fall back to their progress line, e.g.
+/// when UAssetCLI died before printing the summary.
+fn finished_results(
+    items: &[BatchItem],
+    stdout: &str,
+    exit_code: i32,
+    progress: &[ConversionProgress],
+    to_json: bool,
+) -> Vec<SingleConversionResult> {
+    let summary = serde_json::from_str::<serde_json::Value>(stdout).ok();
+    if summary.is_none() {
+        eprintln!("[DEBUG] Failed to parse stdout as JSON");
+    }
+    // Set when the whole run failed, e.g. the usmap could not be loaded
+    let tool_error = summary
+        .as_ref()
+        .filter(|s| s.get("success").and_then(|v| v.as_bool()) == Some(false))
+        .map(|_| AppError::from_tool(exit_code, stdout));
+    let records = summary
+        .as_ref()
+        .and_then(|s| s.get("results"))
+        .and_then(|r| r.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let (input_key, output_key) = if to_json {
+        ("uassetPath", "jsonPath")
+    } else {
+        ("jsonPath", "uassetPath")
+    };
+
+    let mut used = vec![false; records.len()];
+    let reports = match_progress(items, progress);
+
+    items
+        .iter()
+        .zip(reports)
+        .map(|(item, report)| {
+            let record = records.iter().enumerate().position(|(i, r)| {
+                !used[i]
+                    && match r.get(input_key).and_then(|p| p.as_str()) {
+                        Some(input_path) => input_path == item.input_path,
+                        None => r.get("fileName").and_then(|f| f.as_str()) == Some(&item.file_name),
+                    }
+            });
+            let (success, error, output_path) = match record {
+                Some(i) => {
+                    used[i] = true;
+                    let record = &records[i];
+                    let success = record
+                        .get("success")
+                        .and_then(|s| s.as_bool())
+                        .unwrap_or(false);
+                    let error = record
+                        .get("error")
+                        .and_then(|s| s.as_str())
+                        .map(|e| AppError::from_batch_file(e, &item.input_path));
+                    let output_path = record
+                        .get(output_key)
+                        .and_then(|s| s.as_str())
+                        .map(|s| s.to_string());
+                    (success, error, output_path)
+                }
+                None => match report {
+                    Some(p) => (p.error.is_none(), progress_error(p, &item.input_path), None),
+                    None => {
+                        let error = match &tool_error {
+                            Some(e) => e.clone(),
+                            None => AppError::new(
+                                ErrorCode::ToolFailed,
+                                "UAssetCLI reported no result for this file",
+                            ),
+                        };
+                        (false, Some(error.with_path(&item.input_path)), None)
+                    }
+                },
+            };
+            let output_path =
+                output_path.unwrap_or_else(|| item.output_path.to_string_lossy().to_string());
+
+            let (uasset_path, json_path) = if to_json {
+                (item.input_path.clone(), success.then_some(output_path))
+            } else {
+                (output_path, Some(item.input_path.clone()))
+            };
+            SingleConversionResult {
+                success,
+                file_name: item.file_name.clone(),
+                uasset_path,
+                json_path,
+                cached: false,
+                cache_miss: item.cache_miss,
+                error,
+                aborted: false,
+                round_trip: None,
+                summary: None,
+            }
+        })
+        .collect()
+}
+
+/// Compares the JSON an asset was written from with the JSON UAssetCLI read
+/// back from it.
+fn round_trip_report(expected_path: &Path, actual_path: &Path) -> RoundTripReport {
+    let read = |path: &Path| -> Result<serde_json::Value, AppError> {
+        let content = fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            AppError::new(ErrorCode::AssetParseFailed, format!("Invalid JSON: {}", e))
+                .with_path(path)
+        })
+    };
+    match (read(expected_path), read(actual_path)) {
+        (Ok(expected), Ok(actual)) => verify::compare(&expected, &actual),
+        (Err(e), _) | (_, Err(e)) => RoundTripReport::failed(e),
+    }
+}
+
+/// Reads every successfully written asset in `results` back to JSON in a
+/// temp dir and attaches its round-trip report. Returns the reply channel if
+/// the conversion was cancelled meanwhile.
+async fn verify_written(
+    state: &AppState,
+    tool_path: &Path,
+    options: &ToolOptions,
+    results: &mut [SingleConversionResult],
+    conversion: &mut ActiveConversion<'_>,
+    on_progress: ProgressCallback,
+) -> Result<Option<CancelReply>, AppError> {
+    let targets: Vec<usize> = (0..results.len())
+        .filter(|&i| results[i].success && results[i].json_path.is_some())
+        .collect();
+    if targets.is_empty() {
+        return Ok(None);
+    }
+    let temp_dir = tempfile::tempdir().map_err(|e| AppError::io(std::env::temp_dir(), e))?;
+
+    let items: Vec<BatchItem> = targets
+        .iter()
+        .map(|&i| {
+            let result = &results[i];
+            let relative = format!("{}.json", i);
+            BatchItem {
+                file_name: result.file_name.clone(),
+                input_path: result.uasset_path.clone(),
+                output_path: temp_dir.path().join(&relative),
+                list_line: format!("{},{}", result.uasset_path, relative),
+                cache_miss: None,
+            }
+        })
+        .collect();
+    let concurrency = state.settings.lock().unwrap().conversion_concurrency;
+    let shards = shard_items(items, concurrency);
+    let list_files = write_batch_lists(temp_dir.path(), "verify", &shards)?;
+    let shard_args: Vec<_> = list_files
+        .iter()
+        .map(|list_file| tool_args("batch-to-json", &[list_file, temp_dir.path()], options))
+        .collect();
+
+    let on_progress: ProgressCallback = Arc::new(move |progress| {
+        on_progress(&ConversionProgress {
+            progress_type: Some("verify".to_string()),
+            file_name: format!("Verifying {}", progress.file_name),
+            ..progress.clone()
+        })
+    });
+    let on_progress = merged_progress(on_progress, 0, targets.len());
+    let outputs =
+        match run_batch_tool(state, tool_path, &shard_args, conversion, on_progress).await? {
+            BatchOutcome::Completed(outputs) => outputs,
+            BatchOutcome::Cancelled { reply, .. } => return Ok(Some(reply)),
+        };
+
+    for (shard, output) in shards.iter().zip(&outputs) {
+        let read_back = finished_results(
+            shard,
+            &output.stdout,
+            output.exit_code,
+            &output.progress,
+            true,
+        );
+        for (item, read_back) in shard.iter().zip(read_back) {
+            let Some(i) = targets.iter().copied().find(|&i| {
+                results[i].round_trip.is_none() && results[i].uasset_path == item.input_path
+            }) else {
+                continue;
+            };
+            let report = match (read_back.success, read_back.error) {
+                (true, _) => round_trip_report(
+                    Path::new(results[i].json_path.as_deref().unwrap_or_default()),
+                    &item.output_path,
+                ),
+                (false, Some(error)) => RoundTripReport::failed(error),
+                (false, None) => RoundTripReport::failed(AppError::new(
+                    ErrorCode::ToolFailed,
+                    "UAssetCLI could not read the written file back",
+                )),
+            };
+            if !report.is_clean() {
+                eprintln!(
+                    "[DEBUG] Round trip of {} found {} mismatch(es)",
+                    results[i].file_name, report.mismatch_count
+                );
+            }
+            results[i].round_trip = Some(report);
+        }
+    }
+    Ok(None)
+}
+
+/// Removes `path`, and the `.uexp`/`.ubulk` files UAssetAPI writes next to
+/// it, if they were written after `started`, leaving outputs of earlier runs
+/// alone.
+fn remove_partial_output(path: &Path, started: SystemTime) {
+    let (Some(dir), Some(stem)) = (path.parent(), path.file_stem()) else {
+        return;
+    };
+    let Ok(siblings) = fs::read_dir(dir) else {
+        return;
+    };
+    for sibling in siblings.flatten().map(|entry| entry.path()) {
+        if sibling.file_stem() != Some(stem) {
+            continue;
+        }
+        let modified = fs::metadata(&sibling).and_then(|m| m.modified());
+        if modified.is_ok_and(|m| m >= started) {
+            eprintln!("[DEBUG] Removing partial output: {:?}", sibling);
+            let _ = fs::remove_file(&sibling);
+        }
+    }
+}
+
+// ============================================================================
+// TAURI COMMANDS
+// ============================================================================
+
+#[tauri::command]
+fn get_exe_dir() -> Result<String, AppError> {
+    let exe =
+        std::env::current_exe().map_err(|e| AppError::new(ErrorCode::IoError, e.to_string()))?;
+    exe.parent()
+        .ok_or_else(|| AppError::io(&exe, "Could not get exe parent directory"))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn get_settings(state: State<AppState>) -> AppSettings {
+    state.settings.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_layered_settings(state: State<AppState>) -> LayeredSettings {
+    state.layers.lock().unwrap().describe()
+}
+
+/// Layers the `.rvfxe.json` of `path` over the global settings, or goes back
+/// to the global settings alone for `None`. Refused during a conversion only
+/// if the folder uses another cache directory.
+#[tauri::command]
+fn open_project_folder(
+    path: Option<String>,
+    state: State<AppState>,
+) -> Result<LayeredSettings, AppError> {
+    let old_dir = state.cache_dir();
+    let (opened, _conversion) = {
+        let mut layers = state.layers.lock().unwrap();
+        let mut next = layers.clone();
+        let opened = next.open_project(path.as_deref().map(Path::new));
+        let conversion = guard_cache_move(&state, &old_dir, &next.effective())?;
+        *layers = next;
+        (opened, conversion)
+    };
+    let layered = apply_settings_layers(&state);
+    opened.map(|_| layered)
+}
+
+/// Keeps `key` in the open folder's settings file, or stops overriding it.
+#[tauri::command]
+fn set_project_override(
+    key: String,
+    enabled: bool,
+    state: State<AppState>,
+) -> Result<LayeredSettings, AppError> {
+    let old_dir = state.cache_dir();
+    let _conversion = {
+        let mut layers = state.layers.lock().unwrap();
+        let mut preview = layers.clone();
+        preview.change_override(&key, enabled)?;
+        let conversion = guard_cache_move(&state, &old_dir, &preview.effective())?;
+        layers.set_override(&key, enabled)?;
+        conversion
+    };
+    Ok(apply_settings_layers(&state))
+}
+
+#[tauri::command]
+fn set_usmap_path(path: String, state: State<AppState>) -> Result<(), AppError> {
+    mappings::validate(Path::new(&path))?;
+
+    update_settings(&state, |settings| {
+        settings.usmap_path = Some(path);
+        settings.active_mapping = None;
+    })
+}
+
+#[tauri::command]
+fn validate_usmap(path: String) -> Result<UsmapSummary, AppError> {
+    let usmap_path = PathBuf::from(&path);
+    let usmap = Usmap::from_path(&usmap_path).map_err(|e| AppError::usmap(&usmap_path, e))?;
+    Ok(usmap.summary(&usmap_path))
+}
+
+#[tauri::command]
+fn list_mappings(state: State<AppState>) -> Result<Vec<MappingInfo>, AppError> {
+    let active = state.settings.lock().unwrap().active_mapping.clone();
+    mappings::list(active.as_deref())
+}
+
+#[tauri::command]
+fn import_mapping(
+    path: String,
+    label: Option<String>,
+    state: State<AppState>,
+) -> Result<MappingImport, AppError> {
+    let active = state.settings.lock().unwrap().active_mapping.clone();
+    mappings::import(Path::new(&path), label.as_deref(), active.as_deref())
+}
+
+/// Converts with the library mapping `name`, or with `usmap_path` again for
+/// `None`. Saved to the project file if it overrides the active mapping.
+pub(crate) fn use_mapping(state: &AppState, name: Option<&str>) -> Result<(), AppError> {
+    let hash = match name {
+        Some(name) => Some(mappings::resolve(name)?.hash),
+        None => None,
+    };
+    update_settings(state, |settings| settings.active_mapping = hash)
+}
+
+#[tauri::command]
+fn set_active_mapping(name: Option<String>, state: State<AppState>) -> Result<(), AppError> {
+    use_mapping(&state, name.as_deref())
+}
+
+#[tauri::command]
+fn relabel_mapping(name: String, label: String) -> Result<MappingEntry, AppError> {
+    mappings::relabel(&name, &label)
+}
+
+/// Deletes a library mapping. The one in use cannot be deleted.
+pub(crate) fn remove_mapping(state: &AppState, name: &str) -> Result<MappingEntry, AppError> {
+    let entry = mappings::resolve(name)?;
+    if state.settings.lock().unwrap().active_mapping.as_deref() == Some(entry.hash.as_str()) {
+        return Err(AppError::new(
+            ErrorCode::InvalidSetting,
+            format!(
+                "Mapping \"{}\" is in use, switch to another one first",
+                entry.label
+            ),
+        ));
+    }
+    mappings::remove(&entry.hash)
+}
+
+#[tauri::command]
+fn delete_mapping(name: String, state: State<AppState>) -> Result<MappingEntry, AppError> {
+    remove_mapping(&state, &name)
+}
+
+#[tauri::command]
+fn install_mapping_for_uassetgui(name: String) -> Result<String, AppError> {
+    mappings::install_for_uassetgui(&name)
+}
+
+#[tauri::command]
+fn set_detailed_errors(enabled: bool, state: State<AppState>) -> Result<(), AppError> {
+    update_settings(&state, |settings| settings.show_detailed_errors = enabled)
+}
+
+#[tauri::command]
+fn set_uasset_cli_path(path: Option<String>, state: State<AppState>) -> Result<(), AppError> {
+    if let Some(p) = &path {
+        if !Path::new(p).is_file() {
+            return Err(AppError::new(
+                ErrorCode::ToolNotFound,
+                format!("UAssetCLI not found: {}", p),
+            )
+            .with_path(p));
+        }
+    }
+    update_settings(&state, |settings| settings.uasset_cli_path = path)
+}
+
+#[tauri::command]
+async fn check_conversion_tool(app: AppHandle) -> Result<ConversionToolInfo, AppError> {
+    Ok(check_tool(&locate_uasset_tool(&app)).await)
+}
+
+/// Launches the tool's help and `version` commands to prove it actually runs.
+async fn check_tool(location: &ToolLocation) -> ConversionToolInfo {
+    let dotnet = is_dotnet_assembly(&location.path);
+    let mut info = ConversionToolInfo {
+        path: location.path.to_string_lossy().to_string(),
+        source: location.source.to_string(),
+        found: location.path.is_file(),
+        runtime: if dotnet { "dotnet" } else { "native" }.to_string(),
+        runtime_version: None,
+        tool_version: None,
+        error: None,
+    };
+    if !info.found {
+        info.error = Some(format!("UAssetCLI not found at {}", info.path));
+        return info;
+    }
+
+    if dotnet {
+        match hidden_command("dotnet")
+            .arg("--list-runtimes")
+            .output()
+            .await
+        {
+            Ok(output) => {
+                // Lines look like "Microsoft.NETCore.App 8.0.4 [/usr/share/dotnet/...]"
+                let versions: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(|l| l.strip_prefix("Microsoft.NETCore.App "))
+                    .filter_map(|l| l.split_whitespace().next())
+                    .map(str::to_string)
+                    .collect();
+                info.runtime_version = Some(versions.join(", ")).filter(|v| !v.is_empty());
+            }
+            Err(e) => {
+                info.error = Some(format!("dotnet could not be started: {}", e));
+                return info;
+            }
+        }
+    }
+
+    // The usage text exits with 1, so only its content says whether it ran
+    match tool_command(&location.path).arg("help").output().await {
+        Ok(output) if String::from_utf8_lossy(&output.stdout).contains("UAssetCLI") => {}
+        Ok(output) => {
+            info.error = Some(format!(
+                "Unexpected output from UAssetCLI help:\n{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+            return info;
+        }
+        Err(e) => {
+            info.error = Some(format!("UAssetCLI could not be started: {}", e));
+            return info;
+        }
+    }
+
+    if let Ok(output) = tool_command(&location.path).arg("version").output().await {
+        info.tool_version = parse_tool_version(&String::from_utf8_lossy(&output.stdout));
+    }
+    info
+}
+
+#[tauri::command]
+fn set_conversion_concurrency(concurrency: usize, state: State<AppState>) -> Result<(), AppError> {
+    update_settings(&state, |settings| {
+        settings.conversion_concurrency = concurrency.clamp(1, MAX_CONVERSION_CONCURRENCY)
+    })
+}
+
+#[tauri::command]
+fn set_verify_round_trip(enabled: bool, state: State<AppState>) -> Result<(), AppError> {
+    update_settings(&state, |settings| settings.verify_round_trip = enabled)
+}
+
+/// Asks UAssetCLI which engine versions it can read, so the list always
+/// matches the bundled UAssetAPI.
+async fn engine_versions(
+    state: &AppState,
+    tool_path: &Path,
+) -> Result<EngineVersionList, AppError> {
+    let output = state
+        .worker
+        .lock()
+        .await
+        .primary()
+        .request(
+            tool_path,
+            &["engine-versions".to_string()],
+            Arc::new(|_| {}),
+        )
+        .await?;
+    if output.exit_code != 0 {
+        return Err(AppError::from_tool(output.exit_code, &output.stdout));
+    }
+    serde_json::from_str(&output.stdout).map_err(|e| {
+        AppError::new(
+            ErrorCode::ToolFailed,
+            format!("Invalid engine-versions output: {}", e),
+        )
+        .with_tool_output(output.stdout.clone())
+    })
+}
+
+#[tauri::command]
+async fn list_engine_versions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<EngineVersionList, AppError> {
+    engine_versions(&state, &get_uasset_tool_path(&app)).await
+}
+
+#[tauri::command]
+async fn set_engine_version(
+    app: AppHandle,
+    version: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let list = engine_versions(&state, &get_uasset_tool_path(&app)).await?;
+    if !list.versions.contains(&version) {
+        return Err(AppError::new(
+            ErrorCode::InvalidSetting,
+            format!("Unsupported engine version: {}", version),
+        ));
+    }
+    update_settings(&state, |settings| settings.engine_version = version)
+}
+
+#[tauri::command]
+fn set_auto_clear_cache(enabled: bool, state: State<AppState>) -> Result<(), AppError> {
+    update_settings(&state, |settings| settings.auto_clear_cache = enabled)
+}
+
+#[tauri::command]
+fn set_filter_dictionary(
+    dictionary: FilterDictionary,
+    state: State<AppState>,
+) -> Result<(), AppError> {
+    FilterMatcher::new(&dictionary)?;
+    let link = state
+        .settings
+        .lock()
+        .unwrap()
+        .filter_profiles
+        .active_link()
+        .map(Path::to_path_buf);
+    if let Some(link) = link {
+        profiles::write_profile(&dictionary, &link)?;
+    }
+    update_settings(&state, |settings| {
+        settings.filter_profiles.set_active(dictionary)
+    })
+}
+
+/// Runs parameter names through `dictionary`, or the active profile when
+/// none is given, and tells which rule decided each one.
+#[tauri::command]
+fn test_filter(
+    names: Vec<String>,
+    dictionary: Option<FilterDictionary>,
+    state: State<AppState>,
+) -> Result<Vec<FilterDecision>, AppError> {
+    let dictionary = dictionary.unwrap_or_else(|| {
+        state
+            .settings
+            .lock()
+            .unwrap()
+            .filter_profiles
+            .active()
+            .clone()
+    });
+    let matcher = FilterMatcher::new(&dictionary)?;
+    Ok(names.iter().map(|name| matcher.decide(name)).collect())
+}
+
+/// Lists the color parameters of converted assets that pass `dictionary`,
+/// or the active filter profile if none is given. A file that cannot be read
+/// is reported in `failed` without stopping the others.
+#[tauri::command]
+async fn extract_color_params(
+    sources: Vec<ColorSource>,
+    dictionary: Option<FilterDictionary>,
+    state: State<'_, AppState>,
+) -> Result<ColorExtraction, AppError> {
+    let dictionary = dictionary.unwrap_or_else(|| {
+        state
+            .settings
+            .lock()
+            .unwrap()
+            .filter_profiles
+            .active()
+            .clone()
+    });
+    let matcher = Arc::new(FilterMatcher::new(&dictionary)?);
+    let names = Arc::new(dictionary.color_property_names);
+    let file_count = sources.len();
+
+    let read = map_blocking(sources, move |source| {
+        let extracted = source
+            .read()
+            .map(|json| colors::extract_params(&json, &source.relative_path, &names, &matcher));
+        Some((source.relative_path, extracted))
+    })
+    .await;
+
+    let mut extraction = ColorExtraction::default();
+    for (relative_path, extracted) in read {
+        match extracted {
+            Ok((params, filtered_out)) => {
+                extraction.params.extend(params);
+                extraction.filtered_out += filtered_out;
+            }
+            Err(error) => extraction.failed.push(ExtractionFailure {
+                relative_path,
+                error,
+            }),
+        }
+    }
+    eprintln!(
+        "[DEBUG] Extracted {} color params from {} files ({} filtered out, {} failed)",
+        extraction.params.len(),
+        file_count,
+        extraction.filtered_out,
+        extraction.failed.len()
+    );
+    Ok(extraction)
+}
 
-    eprintln!("[DEBUG] Falling back to bundled path: {:?}", bundled_path);
-    bundled_path
+/// Applies `change` to the filter profiles and saves them.
+fn update_filter_profiles(
+    state: &AppState,
+    change: impl FnOnce(&mut FilterProfiles) -> Result<(), AppError>,
+) -> Result<FilterProfiles, AppError> {
+    let mut profiles = state.settings.lock().unwrap().filter_profiles.clone();
+    change(&mut profiles)?;
+    update_settings(state, |settings| {
+        settings.filter_profiles = profiles.clone()
+    })?;
+    Ok(profiles)
 }
 
-// ============================================================================
-// TAURI COMMANDS
-// ============================================================================
+#[tauri::command]
+fn create_filter_profile(name: String, state: State<AppState>) -> Result<FilterProfiles, AppError> {
+    update_filter_profiles(&state, |profiles| {
+        profiles.create(&name, FilterDictionary::default())
+    })
+}
 
 #[tauri::command]
-fn get_exe_dir() -> Result<String, String> {
-    std::env::current_exe()
-        .map_err(|e| e.to_string())?
-        .parent()
-        .ok_or_else(|| "Could not get exe parent directory".to_string())
-        .map(|p| p.to_string_lossy().to_string())
+fn duplicate_filter_profile(
+    name: String,
+    new_name: String,
+    state: State<AppState>,
+) -> Result<FilterProfiles, AppError> {
+    update_filter_profiles(&state, |profiles| profiles.duplicate(&name, &new_name))
 }
 
 #[tauri::command]
-fn get_settings(state: State<AppState>) -> AppSettings {
-    state.settings.lock().unwrap().clone()
+fn rename_filter_profile(
+    name: String,
+    new_name: String,
+    state: State<AppState>,
+) -> Result<FilterProfiles, AppError> {
+    update_filter_profiles(&state, |profiles| profiles.rename(&name, &new_name))
 }
 
 #[tauri::command]
-fn set_usmap_path(path: String, state: State<AppState>) -> Result<(), String> {
-    let mut settings = state.settings.lock().unwrap();
-    settings.usmap_path = Some(path);
-    save_settings(&settings)
+fn delete_filter_profile(name: String, state: State<AppState>) -> Result<FilterProfiles, AppError> {
+    update_filter_profiles(&state, |profiles| profiles.delete(&name))
 }
 
 #[tauri::command]
-fn set_detailed_errors(enabled: bool, state: State<AppState>) -> Result<(), String> {
-    let mut settings = state.settings.lock().unwrap();
-    settings.show_detailed_errors = enabled;
-    save_settings(&settings)
+fn switch_filter_profile(name: String, state: State<AppState>) -> Result<FilterProfiles, AppError> {
+    update_filter_profiles(&state, |profiles| profiles.switch(&name))
 }
 
+/// Adds the dictionary in `path` as a new, active profile named after the
+/// file. A `link`ed profile follows later changes to the file.
 #[tauri::command]
-fn set_auto_clear_cache(enabled: bool, state: State<AppState>) -> Result<(), String> {
-    let mut settings = state.settings.lock().unwrap();
-    settings.auto_clear_cache = enabled;
-    save_settings(&settings)
+fn import_filter_profile(
+    path: String,
+    link: Option<bool>,
+    state: State<AppState>,
+) -> Result<FilterProfiles, AppError> {
+    let path = PathBuf::from(path);
+    let dictionary = profiles::read_profile(&path)?;
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    update_filter_profiles(&state, |profiles| {
+        let name = profiles.unused_name(&stem);
+        profiles.create(&name, dictionary)?;
+        if link.unwrap_or(false) {
+            profiles
+                .links
+                .insert(name, path.to_string_lossy().to_string());
+        }
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn set_filter_dictionary(
-    dictionary: FilterDictionary,
+fn export_filter_profile(
+    name: String,
+    path: String,
     state: State<AppState>,
-) -> Result<(), String> {
-    let mut settings = state.settings.lock().unwrap();
-    settings.filter_dictionary = dictionary;
-    save_settings(&settings)
+) -> Result<(), AppError> {
+    let dictionary = state
+        .settings
+        .lock()
+        .unwrap()
+        .filter_profiles
+        .get(&name)?
+        .clone();
+    profiles::write_profile(&dictionary, Path::new(&path))
 }
 
 #[tauri::command]
-fn get_cache_info() -> CacheInfo {
-    let cache_dir = get_cache_dir();
-    let mut file_count: usize = 0;
-    let mut total_size = 0u64;
+fn get_cache_info(state: State<AppState>) -> CacheInfo {
+    cache_info(&state)
+}
 
+/// Number and total size of the files under `dir`.
+fn dir_stats(dir: &Path) -> (usize, u64) {
     fn visit_dirs(dir: &Path, count: &mut usize, size: &mut u64) -> std::io::Result<()> {
         if dir.is_dir() {
             for entry in fs::read_dir(dir)? {
@@ -411,27 +2416,362 @@ fn get_cache_info() -> CacheInfo {
         Ok(())
     }
 
-    if cache_dir.exists() {
-        let _ = visit_dirs(&cache_dir, &mut file_count, &mut total_size);
+    let mut file_count: usize = 0;
+    let mut total_size = 0u64;
+    if dir.exists() {
+        let _ = visit_dirs(dir, &mut file_count, &mut total_size);
     }
+    (file_count, total_size)
+}
+
+fn cache_info(state: &AppState) -> CacheInfo {
+    let cache_dir = state.cache_dir();
+    let (file_count, total_size) = dir_stats(&cache_dir);
+
+    let json_paths: std::collections::HashSet<_> = {
+        let cache_index = state.cache_index.lock().unwrap();
+        cache_index
+            .entries
+            .values()
+            .map(|entry| entry.json_path.clone())
+            .chain(
+                cache_index
+                    .imported
+                    .values()
+                    .flatten()
+                    .map(|entry| entry.json_path.clone()),
+            )
+            .collect()
+    };
+    let evictable_bytes = json_paths
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
 
     CacheInfo {
         file_count,
         total_size_bytes: total_size,
         cache_dir: cache_dir.to_string_lossy().to_string(),
+        evictable_bytes,
+        max_cache_bytes: state.settings.lock().unwrap().max_cache_bytes,
+    }
+}
+
+#[tauri::command]
+fn set_max_cache_bytes(max_bytes: Option<u64>, state: State<AppState>) -> Result<(), AppError> {
+    update_settings(&state, |settings| {
+        settings.max_cache_bytes = max_bytes.filter(|b| *b > 0)
+    })
+}
+
+#[tauri::command]
+async fn set_cache_location(
+    location: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CacheMoveReport, AppError> {
+    move_cache(&state, location)
+}
+
+/// Moves the cache into `location` (the temp dir for `None`) and rewrites the
+/// index so cached JSON stays valid. Holds the conversion slot so no batch
+/// writes into the old directory meanwhile.
+fn move_cache(state: &AppState, location: Option<String>) -> Result<CacheMoveReport, AppError> {
+    let _conversion = ActiveConversion::begin(state)?;
+    let old_dir = state.cache_dir();
+    let mut settings = state.settings.lock().unwrap().clone();
+    settings.cache_location = location.filter(|l| !l.trim().is_empty());
+    let new_dir = get_cache_dir(&settings);
+
+    let mut report = CacheMoveReport {
+        cache_dir: new_dir.to_string_lossy().to_string(),
+        moved_files: 0,
+        moved_bytes: 0,
+        rewritten_entries: 0,
+    };
+    if new_dir == old_dir {
+        return Ok(report);
+    }
+    if new_dir.starts_with(&old_dir) || old_dir.starts_with(&new_dir) {
+        return Err(AppError::new(
+            ErrorCode::InvalidSetting,
+            "The new cache location overlaps the current cache",
+        )
+        .with_path(&new_dir));
+    }
+
+    // A rename is instant and atomic, but only works within one drive and
+    // into a folder that does not exist yet
+    let mut copied = false;
+    if old_dir.exists() {
+        (report.moved_files, report.moved_bytes) = dir_stats(&old_dir);
+        if let Some(parent) = new_dir.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        if new_dir.exists() || fs::rename(&old_dir, &new_dir).is_err() {
+            copy_tree(&old_dir, &new_dir)?;
+            copied = true;
+        }
+    }
+    eprintln!(
+        "[DEBUG] Moved {} cache files ({} bytes) from {:?} to {:?}",
+        report.moved_files, report.moved_bytes, old_dir, new_dir
+    );
+
+    let mut moved_index = state.cache_index.lock().unwrap().clone();
+    let json_paths = moved_index
+        .entries
+        .values_mut()
+        .map(|entry| &mut entry.json_path)
+        .chain(
+            moved_index
+                .imported
+                .values_mut()
+                .flatten()
+                .map(|entry| &mut entry.json_path),
+        );
+    for json_path in json_paths {
+        if let Ok(relative) = Path::new(json_path.as_str()).strip_prefix(&old_dir) {
+            *json_path = new_dir.join(relative).to_string_lossy().to_string();
+            report.rewritten_entries += 1;
+        }
+    }
+    *state.cache_index.lock().unwrap() = save_cache_index(&new_dir, moved_index)?;
+    update_settings(state, |current| {
+        current.cache_location = settings.cache_location.clone()
+    })?;
+
+    // Only delete the old copy once the index and settings point at the new one
+    if copied {
+        if let Err(e) = fs::remove_dir_all(&old_dir) {
+            eprintln!("[DEBUG] Failed to remove old cache {:?}: {}", old_dir, e);
+        }
+    }
+    Ok(report)
+}
+
+/// Copies every file under `from` into `to`, keeping relative paths.
+fn copy_tree(from: &Path, to: &Path) -> Result<(), AppError> {
+    fs::create_dir_all(to).map_err(|e| AppError::io(to, e))?;
+    for entry in fs::read_dir(from).map_err(|e| AppError::io(from, e))? {
+        let entry = entry.map_err(|e| AppError::io(from, e))?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if source.is_dir() {
+            copy_tree(&source, &target)?;
+        } else {
+            fs::copy(&source, &target).map_err(|e| AppError::io(&target, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes least-recently-used JSON until the cache fits `max_cache_bytes`,
+/// then saves the index. Entries used since `batch_started` are kept so a
+/// batch never evicts the files it is about to return.
+fn enforce_cache_budget(state: &AppState, batch_started: chrono::DateTime<chrono::Utc>) {
+    let budget = state.settings.lock().unwrap().max_cache_bytes;
+    let mut total = match budget {
+        Some(_) => cache_info(state).total_size_bytes,
+        None => 0,
+    };
+
+    if let Some(budget) = budget.filter(|b| total > *b) {
+        // Last use, entry key (none for bundle imports) and JSON path
+        let mut candidates: Vec<_> = {
+            let cache_index = state.cache_index.lock().unwrap();
+            let entries = cache_index.entries.iter().map(|(key, entry)| {
+                (
+                    entry.last_used(),
+                    Some(key.clone()),
+                    entry.json_path.clone(),
+                )
+            });
+            let imported = cache_index.imported.values().flatten().map(|entry| {
+                let imported_at = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+                    .ok()
+                    .map(|t| t.with_timezone(&chrono::Utc));
+                (imported_at, None, entry.json_path.clone())
+            });
+            entries
+                .chain(imported)
+                .filter(|(last_used, _, _)| last_used.is_none_or(|t| t < batch_started))
+                .collect()
+        };
+        candidates.sort();
+
+        let mut evicted = 0;
+        for (_, key, json_path) in candidates {
+            if total <= budget {
+                break;
+            }
+            // Drop the entry first, so nothing is served from a file being deleted
+            let removed = {
+                let mut cache_index = state.cache_index.lock().unwrap();
+                match &key {
+                    Some(key) => cache_index.entries.remove(key).is_some(),
+                    None => {
+                        let mut removed = false;
+                        for imported in cache_index.imported.values_mut() {
+                            let count = imported.len();
+                            imported.retain(|entry| entry.json_path != json_path);
+                            removed |= imported.len() < count;
+                        }
+                        cache_index
+                            .imported
+                            .retain(|_, imported| !imported.is_empty());
+                        removed
+                    }
+                }
+            };
+            if !removed {
+                continue;
+            }
+            let json_path = Path::new(&json_path);
+            let size = fs::metadata(json_path).map(|m| m.len()).unwrap_or(0);
+            if fs::remove_file(json_path).is_ok() {
+                total = total.saturating_sub(size);
+                // Drop folders the batch layout left empty
+                if let Some(parent) = json_path.parent() {
+                    let _ = fs::remove_dir(parent);
+                }
+            }
+            evicted += 1;
+        }
+        eprintln!(
+            "[DEBUG] Evicted {} cache entries, cache is now {} of {} bytes",
+            evicted, total, budget
+        );
+    }
+
+    if let Err(e) = persist_cache_index(state) {
+        eprintln!("[DEBUG] Failed to save cache index: {}", e);
     }
 }
 
 #[tauri::command]
-fn clear_cache(state: State<AppState>) -> Result<(), String> {
-    let cache_dir = get_cache_dir();
+fn clear_cache(state: State<AppState>) -> Result<(), AppError> {
+    clear_cache_files(&state)
+}
+
+#[tauri::command]
+async fn repair_cache(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CacheRepairReport, AppError> {
+    repair_cache_files(&state, &get_uasset_tool_path(&app)).await
+}
+
+/// Brings the index and the cached files back in line, see `repair::repair`.
+async fn repair_cache_files(
+    state: &AppState,
+    tool_path: &Path,
+) -> Result<CacheRepairReport, AppError> {
+    let _conversion = ActiveConversion::begin(state)?;
+    let cache_dir = state.cache_dir();
+    let options = tool_options(state)?;
+    let key = current_cache_key(state, tool_path, &options).await?;
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let inputs_modified = options
+        .usmap_path
+        .as_deref()
+        .and_then(|p| modified(Path::new(p)))
+        .max(modified(tool_path));
+
+    let mut cache_index = state.cache_index.lock().unwrap().clone();
+    let report = repair::repair(
+        &cache_dir,
+        &mut cache_index,
+        &RepairInputs {
+            key: &key,
+            inputs_modified,
+        },
+    );
+    *state.cache_index.lock().unwrap() = save_cache_index(&cache_dir, cache_index)?;
+    let _ = fs::remove_file(cache_dir.join(CORRUPT_INDEX_NAME));
+
+    eprintln!(
+        "[DEBUG] Cache repair: {} missing, {} unreadable, {} rebuilt, {} orphans removed",
+        report.missing_json.len(),
+        report.unreadable.len(),
+        report.rebuilt.len(),
+        report.orphans_removed.len()
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+async fn export_cache_bundle(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<CacheBundleReport, AppError> {
+    export_bundle(&state, PathBuf::from(path)).await
+}
+
+#[tauri::command]
+async fn import_cache_bundle(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<CacheBundleReport, AppError> {
+    import_bundle(&state, PathBuf::from(path)).await
+}
+
+/// Packs every cached JSON into a bundle for other machines, see `bundle`.
+async fn export_bundle(
+    state: &AppState,
+    bundle_path: PathBuf,
+) -> Result<CacheBundleReport, AppError> {
+    let _conversion = ActiveConversion::begin(state)?;
+    let index = state.cache_index.lock().unwrap().clone();
+    let report = tauri::async_runtime::spawn_blocking(move || bundle::export(&index, &bundle_path))
+        .await
+        .map_err(|e| AppError::new(ErrorCode::IoError, format!("Export failed: {}", e)))??;
+    eprintln!(
+        "[DEBUG] Exported {} cache entries ({} bytes) to {}",
+        report.entries, report.bytes, report.bundle_path
+    );
+    Ok(report)
+}
+
+/// Unpacks a bundle into the cache. Its entries are matched to local assets
+/// by content when they are next converted.
+async fn import_bundle(
+    state: &AppState,
+    bundle_path: PathBuf,
+) -> Result<CacheBundleReport, AppError> {
+    let _conversion = ActiveConversion::begin(state)?;
+    let cache_dir = state.cache_dir();
+    let index = state.cache_index.lock().unwrap().clone();
+    let (imported, report) = tauri::async_runtime::spawn_blocking(move || {
+        bundle::import(&bundle_path, &cache_dir, &index)
+    })
+    .await
+    .map_err(|e| AppError::new(ErrorCode::IoError, format!("Import failed: {}", e)))??;
+
+    {
+        let mut cache_index = state.cache_index.lock().unwrap();
+        for (hash, entry) in imported {
+            cache_index.imported.entry(hash).or_default().push(entry);
+        }
+    }
+    persist_cache_index(state)?;
+    eprintln!(
+        "[DEBUG] Imported {} cache entries ({} bytes), {} already cached",
+        report.entries, report.bytes, report.skipped
+    );
+    Ok(report)
+}
+
+/// Removes every cached JSON and empties the index.
+fn clear_cache_files(state: &AppState) -> Result<(), AppError> {
+    let _conversion = ActiveConversion::begin(state)?;
+    let cache_dir = state.cache_dir();
     if cache_dir.exists() {
-        fs::remove_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+        fs::remove_dir_all(&cache_dir).map_err(|e| AppError::io(&cache_dir, e))?;
     }
 
-    let mut cache_index = state.cache_index.lock().unwrap();
-    cache_index.entries.clear();
-    save_cache_index(&cache_index)
+    *state.cache_index.lock().unwrap() = save_cache_index(&cache_dir, CacheIndex::default())?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -439,7 +2779,7 @@ async fn convert_uasset_to_json(
     app: AppHandle,
     uasset_path: String,
     state: State<'_, AppState>,
-) -> Result<ConversionResult, String> {
+) -> Result<ConversionResult, AppError> {
     let uasset_path_buf = PathBuf::from(&uasset_path);
 
     if !uasset_path_buf.exists() {
@@ -447,85 +2787,119 @@ async fn convert_uasset_to_json(
             success: false,
             json_path: None,
             cached: false,
-            error: Some(format!("File not found: {}", uasset_path)),
+            cache_miss: None,
+            error: Some(
+                AppError::new(
+                    ErrorCode::IoError,
+                    format!("File not found: {}", uasset_path),
+                )
+                .with_path(&uasset_path),
+            ),
+            round_trip: None,
         });
     }
 
-    // Check cache
-    let file_hash = compute_file_hash(&uasset_path_buf)?;
-    let cache_dir = get_cache_dir();
-    fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    let _conversion = ActiveConversion::begin(&state)?;
+    let options = tool_options(&state)?;
+    let tool_path = get_uasset_tool_path(&app);
+    let started = chrono::Utc::now();
 
+    // Check cache
+    let fingerprint = match fingerprint_assets(&state, std::slice::from_ref(&uasset_path))
+        .await
+        .remove(&uasset_path)
     {
-        let cache_index = state.cache_index.lock().unwrap();
-        if let Some(entry) = cache_index.entries.get(&uasset_path) {
-            if entry.hash == file_hash && Path::new(&entry.json_path).exists() {
-                return Ok(ConversionResult {
-                    success: true,
-                    json_path: Some(entry.json_path.clone()),
-                    cached: true,
-                    error: None,
-                });
+        Some(fingerprint) => fingerprint,
+        // Hash again to surface why the asset could not be read
+        None => Fingerprint {
+            hash: compute_file_hash(&uasset_path_buf)?,
+            stat: None,
+        },
+    };
+    let file_hash = fingerprint.hash.clone();
+    let cache_key = current_cache_key(&state, &tool_path, &options).await?;
+    let cache_dir = state.cache_dir();
+    fs::create_dir_all(&cache_dir).map_err(|e| AppError::io(&cache_dir, e))?;
+
+    let snapshot = index_snapshot(
+        &state,
+        std::slice::from_ref(&uasset_path),
+        std::iter::once(&fingerprint),
+    );
+    let lookup = lookup_or_import(&snapshot, &uasset_path, &fingerprint, &cache_key);
+    let cache_miss = match lookup {
+        Ok((json_path, adopted)) => {
+            if let Some(adopted) = adopted {
+                state
+                    .cache_index
+                    .lock()
+                    .unwrap()
+                    .entries
+                    .insert(uasset_path.clone(), adopted);
+                if let Err(e) = persist_cache_index(&state) {
+                    eprintln!("[DEBUG] Failed to save cache index: {}", e);
+                }
             }
+            return Ok(ConversionResult {
+                success: true,
+                json_path: Some(json_path),
+                cached: true,
+                cache_miss: None,
+                error: None,
+                round_trip: None,
+            });
         }
-    }
-
-    // Get usmap path from settings
-    let usmap_path = {
-        let settings = state.settings.lock().unwrap();
-        settings.usmap_path.clone()
+        Err(miss) => miss,
     };
+    eprintln!("[DEBUG] Cache miss for {}: {:?}", uasset_path, cache_miss);
 
     // Run conversion
-    let tool_path = get_uasset_tool_path(&app);
     let file_name = uasset_path_buf
         .file_stem()
         .unwrap_or_default()
         .to_string_lossy();
     let json_path = cache_dir.join(format!("{}_{}.json", file_name, &file_hash[..8]));
 
-    let mut cmd = Command::new(&tool_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-    cmd.arg("to-json").arg(&uasset_path).arg(&json_path);
-
-    if let Some(usmap) = &usmap_path {
-        cmd.arg("--usmap").arg(usmap);
-    }
-
-    let output = cmd.output().await.map_err(|e| e.to_string())?;
+    let args = tool_args("to-json", &[Path::new(&uasset_path), &json_path], &options);
+    let output = state
+        .worker
+        .lock()
+        .await
+        .primary()
+        .request(&tool_path, &args, Arc::new(|_| {}))
+        .await?;
 
-    if output.status.success() {
+    if output.exit_code == 0 {
         // Update cache
-        let mut cache_index = state.cache_index.lock().unwrap();
-        cache_index.entries.insert(
+        state.cache_index.lock().unwrap().entries.insert(
             uasset_path.clone(),
-            CacheEntry {
-                hash: file_hash,
-                json_path: json_path.to_string_lossy().to_string(),
-                uasset_path: uasset_path.clone(),
-                timestamp: chrono::Utc::now().to_rfc3339(),
-            },
+            CacheEntry::new(
+                fingerprint,
+                &json_path.to_string_lossy(),
+                &uasset_path,
+                &cache_key,
+            ),
         );
-        let _ = save_cache_index(&cache_index);
+        enforce_cache_budget(&state, started);
 
         Ok(ConversionResult {
             success: true,
             json_path: Some(json_path.to_string_lossy().to_string()),
             cached: false,
+            cache_miss: Some(cache_miss),
             error: None,
+            round_trip: None,
         })
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
         Ok(ConversionResult {
             success: false,
             json_path: None,
             cached: false,
-            error: Some(format!("{}\n{}", stdout, stderr)),
+            cache_miss: Some(cache_miss),
+            error: Some(
+                AppError::from_tool(output.exit_code, &output.stdout).with_path(&uasset_path),
+            ),
+            round_trip: None,
         })
     }
 }
@@ -536,84 +2910,137 @@ async fn convert_json_to_uasset(
     json_path: String,
     output_path: String,
     state: State<'_, AppState>,
-) -> Result<ConversionResult, String> {
+) -> Result<ConversionResult, AppError> {
     let tool_path = get_uasset_tool_path(&app);
+    let options = tool_options(&state)?;
 
-    let usmap_path = {
-        let settings = state.settings.lock().unwrap();
-        settings.usmap_path.clone()
-    };
-
-    let mut cmd = Command::new(&tool_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-    cmd.arg("from-json").arg(&json_path).arg(&output_path);
-
-    if let Some(usmap) = &usmap_path {
-        cmd.arg("--usmap").arg(usmap);
-    }
+    let args = tool_args(
+        "from-json",
+        &[Path::new(&json_path), Path::new(&output_path)],
+        &options,
+    );
 
     eprintln!("[DEBUG] Running from-json command: {:?}", tool_path);
     eprintln!(
-        "[DEBUG] Args: from-json {} {} {:?}",
-        json_path, output_path, usmap_path
+        "[DEBUG] Args: from-json {} {} {:?} {}",
+        json_path, output_path, options.usmap_path, options.engine_version
     );
     let start = std::time::Instant::now();
-    let output = cmd.output().await.map_err(|e| e.to_string())?;
+    let output = state
+        .worker
+        .lock()
+        .await
+        .primary()
+        .request(&tool_path, &args, Arc::new(|_| {}))
+        .await?;
     eprintln!("[DEBUG] Command completed in {:?}", start.elapsed());
 
-    if output.status.success() {
+    if output.exit_code == 0 {
+        let verify = state.settings.lock().unwrap().verify_round_trip;
+        let round_trip = if verify {
+            Some(verify_single(&state, &tool_path, &json_path, &output_path, &options).await)
+        } else {
+            None
+        };
         Ok(ConversionResult {
             success: true,
             json_path: Some(output_path),
             cached: false,
+            cache_miss: None,
             error: None,
+            round_trip,
         })
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
         Ok(ConversionResult {
             success: false,
             json_path: None,
             cached: false,
-            error: Some(format!("{}\n{}", stdout, stderr)),
+            cache_miss: None,
+            error: Some(
+                AppError::from_tool(output.exit_code, &output.stdout).with_path(&json_path),
+            ),
+            round_trip: None,
         })
     }
 }
 
+/// Round-trip check for a single written asset, see `verify_written`.
+async fn verify_single(
+    state: &AppState,
+    tool_path: &Path,
+    json_path: &str,
+    uasset_path: &str,
+    options: &ToolOptions,
+) -> RoundTripReport {
+    let temp_dir = match tempfile::tempdir() {
+        Ok(dir) => dir,
+        Err(e) => return RoundTripReport::failed(AppError::io(std::env::temp_dir(), e)),
+    };
+    let read_back = temp_dir.path().join("round_trip.json");
+    let args = tool_args("to-json", &[Path::new(uasset_path), &read_back], options);
+    let output = state
+        .worker
+        .lock()
+        .await
+        .primary()
+        .request(tool_path, &args, Arc::new(|_| {}))
+        .await;
+    match output {
+        Ok(output) if output.exit_code == 0 => round_trip_report(Path::new(json_path), &read_back),
+        Ok(output) => RoundTripReport::failed(
+            AppError::from_tool(output.exit_code, &output.stdout).with_path(uasset_path),
+        ),
+        Err(e) => RoundTripReport::failed(e),
+    }
+}
+
 #[tauri::command]
 async fn batch_convert_uassets_to_json(
     app: AppHandle,
     uasset_paths: Vec<String>,
     root_path: String,
     state: State<'_, AppState>,
-) -> Result<BatchConversionResult, String> {
-    let cache_dir = get_cache_dir();
-    fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+) -> Result<BatchConversionResult, AppError> {
+    let tool_path = get_uasset_tool_path(&app);
+    batch_to_json(
+        &state,
+        &tool_path,
+        uasset_paths,
+        &root_path,
+        progress_emitter(&app),
+    )
+    .await
+}
 
-    let usmap_path = {
-        let settings = state.settings.lock().unwrap();
-        settings.usmap_path.clone()
-    };
+/// Converts `uasset_paths` into the cache, skipping files whose hash is
+/// already indexed. Shared by the Tauri command and the headless CLI.
+async fn batch_to_json(
+    state: &AppState,
+    tool_path: &Path,
+    uasset_paths: Vec<String>,
+    root_path: &str,
+    on_progress: ProgressCallback,
+) -> Result<BatchConversionResult, AppError> {
+    let cache_dir = state.cache_dir();
+    fs::create_dir_all(&cache_dir).map_err(|e| AppError::io(&cache_dir, e))?;
+    let mut conversion = ActiveConversion::begin(state)?;
+    let options = tool_options(state)?;
+    let cache_key = current_cache_key(state, tool_path, &options).await?;
+    let batch_started = chrono::Utc::now();
+    let fingerprints = fingerprint_assets(state, &uasset_paths).await;
 
-    let tool_path = get_uasset_tool_path(&app);
     let total = uasset_paths.len();
     let mut results = Vec::new();
     let mut succeeded = 0;
     let mut failed = 0;
     let mut cached_count = 0;
-
-    // Create temp file with list of paths
-    // Format per line: inputPath,outputRelPath
-    let list_file = cache_dir.join("batch_input.txt");
-    let paths_to_convert: Vec<String>;
+    let mut items = Vec::new();
+    let mut hits = Vec::new();
 
     {
-        let cache_index = state.cache_index.lock().unwrap();
-        let mut non_cached_paths = Vec::new();
+        // Look up against a copy of the entries involved, so the file checks
+        // and progress events below run without the index locked
+        let cache_index = index_snapshot(state, &uasset_paths, fingerprints.values());
 
         for uasset_path in &uasset_paths {
             let path_buf = PathBuf::from(uasset_path);
@@ -630,7 +3057,13 @@ async fn batch_convert_uassets_to_json(
                     uasset_path: uasset_path.clone(),
                     json_path: None,
                     cached: false,
-                    error: Some("File not found".to_string()),
+                    cache_miss: None,
+                    error: Some(
+                        AppError::new(ErrorCode::IoError, "File not found").with_path(uasset_path),
+                    ),
+                    aborted: false,
+                    round_trip: None,
+                    summary: None,
                 });
                 failed += 1;
                 continue;
@@ -661,8 +3094,8 @@ async fn batch_convert_uassets_to_json(
 
             let relative_sub_path = if let Some(rel) = char_id_relative_path {
                 rel.with_extension("json").to_string_lossy().to_string()
-            } else if let Ok(rel) = path_buf.strip_prefix(&root_path) {
-                let root_name = Path::new(&root_path).file_name().unwrap_or_default();
+            } else if let Ok(rel) = path_buf.strip_prefix(root_path) {
+                let root_name = Path::new(root_path).file_name().unwrap_or_default();
                 Path::new(root_name)
                     .join(rel)
                     .with_extension("json")
@@ -670,7 +3103,7 @@ async fn batch_convert_uassets_to_json(
                     .to_string()
             } else {
                 // Fallback to flat structure if outside root
-                let root_name = Path::new(&root_path).file_name().unwrap_or_default();
+                let root_name = Path::new(root_path).file_name().unwrap_or_default();
                 Path::new(root_name)
                     .join(&file_name)
                     .with_extension("json")
@@ -678,171 +3111,182 @@ async fn batch_convert_uassets_to_json(
                     .to_string()
             };
 
-            // Expected JSON path in cache
-            let expected_json_path = cache_dir.join(&relative_sub_path);
-
             // Check cache
-            if let Ok(hash) = compute_file_hash(&path_buf) {
-                if let Some(entry) = cache_index.entries.get(uasset_path) {
-                    if entry.hash == hash && Path::new(&entry.json_path).exists() {
-                        results.push(SingleConversionResult {
-                            success: true,
-                            file_name: file_name.clone(),
-                            uasset_path: uasset_path.clone(),
-                            json_path: Some(entry.json_path.clone()),
-                            cached: true,
-                            error: None,
-                        });
-                        succeeded += 1;
-                        cached_count += 1;
-
-                        // Emit progress
-                        let _ = app.emit(
-                            "conversion-progress",
-                            ConversionProgress {
-                                progress_type: Some("progress".to_string()),
-                                current: results.len(),
-                                total,
-                                file_name: file_name.clone(),
-                                cached: true,
-                                error: None,
-                            },
-                        );
-                        continue;
-                    }
+            let lookup = match fingerprints.get(uasset_path) {
+                Some(fingerprint) => {
+                    lookup_or_import(&cache_index, uasset_path, fingerprint, &cache_key)
                 }
-            }
+                None => Err(CacheMiss::AssetUnreadable),
+            };
+            let cache_miss = match lookup {
+                Ok((json_path, adopted)) => {
+                    results.push(SingleConversionResult {
+                        success: true,
+                        file_name: file_name.clone(),
+                        uasset_path: uasset_path.clone(),
+                        json_path: Some(json_path),
+                        cached: true,
+                        cache_miss: None,
+                        error: None,
+                        aborted: false,
+                        round_trip: None,
+                        summary: None,
+                    });
+                    hits.push((uasset_path, adopted));
+                    succeeded += 1;
+                    cached_count += 1;
+
+                    // Emit progress
+                    on_progress(&ConversionProgress {
+                        progress_type: Some("progress".to_string()),
+                        current: results.len(),
+                        total,
+                        file_name: file_name.clone(),
+                        cached: true,
+                        error: None,
+                        input_path: None,
+                    });
+                    continue;
+                }
+                Err(miss) => miss,
+            };
 
-            // Add to list: inputPath,outputRelPath
-            non_cached_paths.push(format!("{},{}", uasset_path, relative_sub_path));
+            // List line format: inputPath,outputRelPath
+            items.push(BatchItem {
+                file_name,
+                input_path: uasset_path.clone(),
+                output_path: cache_dir.join(&relative_sub_path),
+                list_line: format!("{},{}", uasset_path, relative_sub_path),
+                cache_miss: Some(cache_miss),
+            });
         }
+    }
 
-        paths_to_convert = non_cached_paths;
+    {
+        let mut cache_index = state.cache_index.lock().unwrap();
+        for (uasset_path, adopted) in hits {
+            if let Some(adopted) = adopted {
+                cache_index.entries.insert(uasset_path.clone(), adopted);
+            } else if let Some(entry) = cache_index.entries.get_mut(uasset_path) {
+                entry.last_access = Some(batch_started.to_rfc3339());
+                // Touched but unchanged: skip the hash next time
+                entry.stat = fingerprints[uasset_path].stat;
+            }
+        }
     }
 
     // If all cached, return early
-    if paths_to_convert.is_empty() {
+    if items.is_empty() {
+        attach_summaries(state, &mut results).await;
+        enforce_cache_budget(state, batch_started);
         return Ok(BatchConversionResult {
             total,
             succeeded,
             failed,
             cached_count,
             results,
+            cancelled: false,
         });
     }
 
-    // Write paths to temp file
-    fs::write(&list_file, paths_to_convert.join("\n")).map_err(|e| e.to_string())?;
+    // Write one list file per shard
+    let concurrency = state.settings.lock().unwrap().conversion_concurrency;
+    let shards = shard_items(items, concurrency);
+    let list_files = write_batch_lists(&cache_dir, "batch_input", &shards)?;
 
     // Run batch conversion
-    let mut cmd = Command::new(&tool_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-    cmd.arg("batch-to-json")
-        .arg(&list_file)
-        .arg(&cache_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    if let Some(usmap) = &usmap_path {
-        cmd.arg("--usmap").arg(usmap);
+    let shard_args: Vec<_> = list_files
+        .iter()
+        .map(|list_file| tool_args("batch-to-json", &[list_file, &cache_dir], &options))
+        .collect();
+    let on_progress = merged_progress(on_progress, results.len(), total);
+    let outcome = run_batch_tool(state, tool_path, &shard_args, &mut conversion, on_progress).await;
+    for list_file in &list_files {
+        let _ = fs::remove_file(list_file);
     }
-
-    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
-
-    // Take stderr for progress reading
-    let stderr = child.stderr.take();
-    let app_clone = app.clone();
-
-    // Spawn a task to read progress from stderr (non-blocking)
-    let stderr_handle = tokio::spawn(async move {
-        if let Some(stderr) = stderr {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                if let Ok(progress) = serde_json::from_str::<ConversionProgress>(&line) {
-                    let _ = app_clone.emit("conversion-progress", &progress);
-                }
+    let outputs = match outcome? {
+        BatchOutcome::Completed(outputs) => outputs,
+        BatchOutcome::Cancelled {
+            progress,
+            started,
+            reply,
+        } => {
+            for shard in &shards {
+                results.extend(cancelled_results(shard, &progress, started, true));
             }
-        }
-    });
-
-    // Wait for process to complete
-    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
-
-    // Wait for stderr reading to finish
-    let _ = stderr_handle.await;
-
-    // Parse batch results from stdout
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if let Ok(batch_result) = serde_json::from_str::<serde_json::Value>(&stdout) {
-        if let Some(batch_results) = batch_result.get("results").and_then(|r| r.as_array()) {
-            let mut cache_index = state.cache_index.lock().unwrap();
-
-            for result in batch_results {
-                let success = result
-                    .get("success")
-                    .and_then(|s| s.as_bool())
-                    .unwrap_or(false);
-                let file_name = result
-                    .get("fileName")
-                    .and_then(|s| s.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let uasset_path = result
-                    .get("uassetPath")
-                    .and_then(|s| s.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let json_path = result
-                    .get("jsonPath")
-                    .and_then(|s| s.as_str())
-                    .map(|s| s.to_string());
-                let error = result
-                    .get("error")
-                    .and_then(|s| s.as_str())
-                    .map(|s| s.to_string());
-
-                if success {
-                    succeeded += 1;
 
-                    // Update cache
-                    if let (Some(jp), Ok(hash)) =
-                        (&json_path, compute_file_hash(Path::new(&uasset_path)))
+            // Files that made it through are still worth caching
+            {
+                let mut cache_index = state.cache_index.lock().unwrap();
+                for result in results.iter().filter(|r| r.success && !r.cached) {
+                    if let (Some(jp), Some(fingerprint)) =
+                        (&result.json_path, fingerprints.get(&result.uasset_path))
                     {
                         cache_index.entries.insert(
-                            uasset_path.clone(),
-                            CacheEntry {
-                                hash,
-                                json_path: jp.clone(),
-                                uasset_path: uasset_path.clone(),
-                                timestamp: chrono::Utc::now().to_rfc3339(),
-                            },
+                            result.uasset_path.clone(),
+                            CacheEntry::new(
+                                fingerprint.clone(),
+                                jp,
+                                &result.uasset_path,
+                                &cache_key,
+                            ),
                         );
                     }
-                } else {
-                    failed += 1;
                 }
-
-                results.push(SingleConversionResult {
-                    success,
-                    file_name,
-                    uasset_path,
-                    json_path,
-                    cached: false,
-                    error,
-                });
             }
+            attach_summaries(state, &mut results).await;
+            enforce_cache_budget(state, batch_started);
+
+            let succeeded = results.iter().filter(|r| r.success).count();
+            let result = BatchConversionResult {
+                total,
+                succeeded,
+                failed: total - succeeded,
+                cached_count,
+                results,
+                cancelled: true,
+            };
+            let _ = reply.send(result.clone());
+            return Ok(result);
+        }
+    };
 
-            let _ = save_cache_index(&cache_index);
+    let converted: Vec<_> = shards
+        .iter()
+        .zip(&outputs)
+        .flat_map(|(shard, output)| {
+            finished_results(
+                shard,
+                &output.stdout,
+                output.exit_code,
+                &output.progress,
+                true,
+            )
+        })
+        .collect();
+    {
+        let mut cache_index = state.cache_index.lock().unwrap();
+        for result in &converted {
+            if !result.success {
+                failed += 1;
+                continue;
+            }
+            succeeded += 1;
+
+            // Update cache
+            if let (Some(jp), Some(fingerprint)) =
+                (&result.json_path, fingerprints.get(&result.uasset_path))
+            {
+                cache_index.entries.insert(
+                    result.uasset_path.clone(),
+                    CacheEntry::new(fingerprint.clone(), jp, &result.uasset_path, &cache_key),
+                );
+            }
         }
     }
-
-    // Cleanup temp file
-    let _ = fs::remove_file(&list_file);
+    results.extend(converted);
+    attach_summaries(state, &mut results).await;
+    enforce_cache_budget(state, batch_started);
 
     Ok(BatchConversionResult {
         total,
@@ -850,6 +3294,7 @@ async fn batch_convert_uassets_to_json(
         failed,
         cached_count,
         results,
+        cancelled: false,
     })
 }
 
@@ -859,116 +3304,194 @@ async fn batch_convert_jsons_to_uassets(
     state: State<'_, AppState>,
     json_paths: Vec<String>,
     output_dir: String,
-) -> Result<BatchConversionResult, String> {
+) -> Result<BatchConversionResult, AppError> {
     let tool_path = get_uasset_tool_path(&app);
-    let cache_dir = get_cache_dir();
-
-    let usmap_path = {
-        let settings = state.settings.lock().unwrap();
-        settings.usmap_path.clone()
-    };
+    batch_from_json(
+        &state,
+        &tool_path,
+        json_paths,
+        &output_dir,
+        progress_emitter(&app),
+    )
+    .await
+}
 
-    // Create temp file with list of paths (format: jsonPath,outputName per line)
-    let list_file = cache_dir.join("batch_output_list.txt");
-    fs::write(&list_file, json_paths.join("\n")).map_err(|e| e.to_string())?;
+/// Writes each `jsonPath[,outputName]` entry back to a .uasset under
+/// `output_dir`. Shared by the Tauri command and the headless CLI.
+async fn batch_from_json(
+    state: &AppState,
+    tool_path: &Path,
+    json_paths: Vec<String>,
+    output_dir: &str,
+    on_progress: ProgressCallback,
+) -> Result<BatchConversionResult, AppError> {
+    let cache_dir = state.cache_dir();
+    let mut conversion = ActiveConversion::begin(state)?;
+    let options = tool_options(state)?;
 
     // Ensure output directory exists
-    fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(output_dir).map_err(|e| AppError::io(output_dir, e))?;
 
     let total = json_paths.len();
 
+    // Same output naming as UAssetCLI, so a cancelled run knows what to clean up
+    let items: Vec<BatchItem> = json_paths
+        .iter()
+        .map(|line| {
+            let mut parts = line.split(',');
+            let json_path = parts.next().unwrap_or_default().trim().to_string();
+            let file_name = Path::new(&json_path)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let output_name = match parts.next() {
+                Some(name) => PathBuf::from(name.trim()),
+                None => Path::new(&file_name).with_extension("uasset"),
+            };
+            BatchItem {
+                file_name,
+                input_path: json_path,
+                output_path: Path::new(output_dir).join(output_name),
+                list_line: line.clone(),
+                cache_miss: None,
+            }
+        })
+        .collect();
+
     eprintln!(
         "[DEBUG] batch_convert_jsons_to_uassets: total={} files, output_dir={}",
         total, output_dir
     );
     eprintln!("[DEBUG] Tool path: {:?}", tool_path);
-    eprintln!("[DEBUG] Usmap path: {:?}", usmap_path);
+    eprintln!("[DEBUG] Usmap path: {:?}", options.usmap_path);
+    eprintln!("[DEBUG] Engine version: {}", options.engine_version);
+
+    // Create temp files with list of paths (format: jsonPath,outputName per line)
+    let concurrency = state.settings.lock().unwrap().conversion_concurrency;
+    let shards = shard_items(items, concurrency);
+    let list_files = write_batch_lists(&cache_dir, "batch_output_list", &shards)?;
 
     // Run batch conversion
-    let mut cmd = Command::new(&tool_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    let shard_args: Vec<_> = list_files
+        .iter()
+        .map(|list_file| {
+            tool_args(
+                "batch-from-json",
+                &[list_file, Path::new(output_dir)],
+                &options,
+            )
+        })
+        .collect();
+    let batch_progress = merged_progress(on_progress.clone(), 0, total);
+    let outcome = run_batch_tool(
+        state,
+        tool_path,
+        &shard_args,
+        &mut conversion,
+        batch_progress,
+    )
+    .await;
+    for list_file in &list_files {
+        let _ = fs::remove_file(list_file);
     }
-    cmd.arg("batch-from-json")
-        .arg(&list_file)
-        .arg(&output_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    if let Some(usmap) = &usmap_path {
-        cmd.arg("--usmap").arg(usmap);
-    }
-
-    eprintln!("[DEBUG] Spawning batch command...");
-    let spawn_start = std::time::Instant::now();
-    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
-    eprintln!("[DEBUG] Spawn took {:?}", spawn_start.elapsed());
-
-    // Take stderr for progress reading
-    let stderr = child.stderr.take();
-    let app_clone = app.clone();
-
-    // Spawn a task to read progress from stderr (non-blocking)
-    let stderr_handle = tokio::spawn(async move {
-        if let Some(stderr) = stderr {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                if let Ok(progress) = serde_json::from_str::<ConversionProgress>(&line) {
-                    let _ = app_clone.emit("conversion-progress", &progress);
-                }
-            }
+    let outputs = match outcome? {
+        BatchOutcome::Completed(outputs) => outputs,
+        BatchOutcome::Cancelled {
+            progress,
+            started,
+            reply,
+        } => {
+            let results: Vec<_> = shards
+                .iter()
+                .flat_map(|shard| cancelled_results(shard, &progress, started, false))
+                .collect();
+            let succeeded = results.iter().filter(|r| r.success).count();
+            let result = BatchConversionResult {
+                total,
+                succeeded,
+                failed: total - succeeded,
+                cached_count: 0,
+                results,
+                cancelled: true,
+            };
+            let _ = reply.send(result.clone());
+            return Ok(result);
         }
-    });
-
-    // Wait for process to complete
-    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
-
-    // Wait for stderr reading to finish
-    let _ = stderr_handle.await;
-
-    // Parse batch results from stdout
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr_output = String::from_utf8_lossy(&output.stderr);
+    };
 
-    // Log for debugging
-    eprintln!("[DEBUG] batch-from-json stdout length: {}", stdout.len());
-    eprintln!(
-        "[DEBUG] batch-from-json stderr length: {}",
-        stderr_output.len()
-    );
-    let stdout_preview: String = stdout.chars().take(500).collect();
-    eprintln!("[DEBUG] stdout preview: {}", stdout_preview);
-    if !output.status.success() {
-        eprintln!("[DEBUG] Exit code: {:?}", output.status.code());
+    let mut results = Vec::with_capacity(total);
+    for (shard, output) in shards.iter().zip(&outputs) {
+        // Log for debugging
+        eprintln!(
+            "[DEBUG] batch-from-json stdout length: {}",
+            output.stdout.len()
+        );
+        let stdout_preview: String = output.stdout.chars().take(500).collect();
+        eprintln!("[DEBUG] stdout preview: {}", stdout_preview);
+
+        results.extend(finished_results(
+            shard,
+            &output.stdout,
+            output.exit_code,
+            &output.progress,
+            false,
+        ));
     }
-
-    let mut succeeded = 0;
-    let mut failed = total; // Default to all failed
-    let results = Vec::new();
-
-    if let Ok(batch_result) = serde_json::from_str::<serde_json::Value>(&stdout) {
-        succeeded = batch_result
-            .get("succeeded")
-            .and_then(|s| s.as_u64())
-            .unwrap_or(0) as usize;
-        failed = batch_result
-            .get("failed")
-            .and_then(|s| s.as_u64())
-            .unwrap_or(total as u64) as usize;
-    } else {
-        eprintln!("[DEBUG] Failed to parse stdout as JSON");
+    for result in results.iter().filter(|r| !r.success) {
+        if let Some(error) = &result.error {
+            eprintln!("[DEBUG] Failed {}: {}", result.file_name, error);
+        }
     }
+    let succeeded = results.iter().filter(|r| r.success).count();
+
+    let verify = state.settings.lock().unwrap().verify_round_trip;
+    let cancel_reply = if verify {
+        verify_written(
+            state,
+            tool_path,
+            &options,
+            &mut results,
+            &mut conversion,
+            on_progress,
+        )
+        .await?
+    } else {
+        None
+    };
 
-    Ok(BatchConversionResult {
+    // Cancelling during verification keeps everything that was written
+    let result = BatchConversionResult {
         total,
         succeeded,
-        failed,
+        failed: total - succeeded,
         cached_count: 0,
         results,
-    })
+        cancelled: cancel_reply.is_some(),
+    };
+    if let Some(reply) = cancel_reply {
+        let _ = reply.send(result.clone());
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+async fn cancel_conversion(state: State<'_, AppState>) -> Result<BatchConversionResult, AppError> {
+    let not_running = |message: &str| AppError::new(ErrorCode::NotRunning, message);
+    let (reply_tx, reply_rx) = oneshot::channel();
+    {
+        let mut conversion = state.conversion.lock().unwrap();
+        let cancel = conversion
+            .as_mut()
+            .and_then(|c| c.cancel.take())
+            .ok_or_else(|| not_running("No conversion is running"))?;
+        cancel
+            .send(reply_tx)
+            .map_err(|_| not_running("Conversion already finished"))?;
+    }
+    reply_rx
+        .await
+        .map_err(|_| not_running("Conversion finished before it could be cancelled"))
 }
 
 #[tauri::command]
@@ -986,30 +3509,62 @@ fn log_unique_params(param_names: Vec<String>) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let settings = load_settings();
-    let cache_index = load_cache_index();
+    let layers = SettingsLayers::new(load_settings());
+    let cache_index = load_cache_index(&get_cache_dir(&layers.effective()));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(AppState {
-            settings: Mutex::new(settings),
-            cache_index: Mutex::new(cache_index),
+        .manage(AppState::new(layers, cache_index))
+        .setup(|app| {
+            watch::spawn(app.handle().clone());
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_settings,
+            get_layered_settings,
+            open_project_folder,
+            set_project_override,
             set_usmap_path,
+            validate_usmap,
+            list_mappings,
+            import_mapping,
+            set_active_mapping,
+            relabel_mapping,
+            delete_mapping,
+            install_mapping_for_uassetgui,
             set_detailed_errors,
             set_auto_clear_cache,
             set_filter_dictionary,
+            test_filter,
+            extract_color_params,
+            create_filter_profile,
+            duplicate_filter_profile,
+            rename_filter_profile,
+            delete_filter_profile,
+            switch_filter_profile,
+            import_filter_profile,
+            export_filter_profile,
+            set_uasset_cli_path,
+            set_conversion_concurrency,
+            set_verify_round_trip,
+            list_engine_versions,
+            set_engine_version,
+            check_conversion_tool,
             get_cache_info,
+            set_max_cache_bytes,
+            set_cache_location,
+            repair_cache,
+            export_cache_bundle,
+            import_cache_bundle,
             clear_cache,
             convert_uasset_to_json,
             convert_json_to_uasset,
             batch_convert_uassets_to_json,
             batch_convert_jsons_to_uassets,
+            cancel_conversion,
             get_exe_dir,
             log_unique_params,
         ])
@@ -1024,13 +3579,9 @@ pub fn run() {
                 };
 
                 if should_clear {
-                    let cache_dir = get_cache_dir();
-                    if cache_dir.exists() {
-                        let _ = fs::remove_dir_all(&cache_dir);
+                    if let Err(e) = clear_cache_files(&state) {
+                        eprintln!("[DEBUG] Failed to clear cache on exit: {}", e);
                     }
-                    let mut cache_index = state.cache_index.lock().unwrap();
-                    cache_index.entries.clear();
-                    let _ = save_cache_index(&cache_index);
                 }
             }
         });
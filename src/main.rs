@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Subcommands like `convert` run headless and never open a window
+    if let Some(code) = rivals_vfx_editor_lib::cli::run_from_args() {
+        std::process::exit(code);
+    }
     rivals_vfx_editor_lib::run()
 }
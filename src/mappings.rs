@@ -0,0 +1,323 @@
+//! Managed `.usmap` library.
+//!
+//! Every game patch needs a new mapping. Imported mappings are copied into
+//! the app data dir under their content hash and labelled with the patch
+//! they are for, so going back to an older patch is a settings change, not a
+//! hunt for the right file. `activeMapping` in settings, or in a project's
+//! `.rvfxe.json`, names the one conversions use.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, ErrorCode};
+use crate::persist::{self, LockFile};
+use crate::usmap::{Usmap, UsmapError, UsmapSummary};
+
+const INDEX_FILE: &str = "library.json";
+
+/// Name UAssetGUI and the batch scripts load the mapping by.
+const UASSETGUI_MAPPING: &str = "Rivals.usmap";
+
+/// Hash prefixes shorter than this are not accepted as a mapping name.
+const MIN_HASH_PREFIX: usize = 6;
+
+/// Label for an imported file with no usable name.
+const DEFAULT_LABEL: &str = "Mapping";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MappingEntry {
+    /// SHA-256 of the file, also its name in the library.
+    pub hash: String,
+    /// Game patch the mapping is for, e.g. `S5.5`.
+    pub label: String,
+    /// Name of the file it was imported from.
+    pub file_name: String,
+    pub imported_at: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct LibraryIndex {
+    mappings: Vec<MappingEntry>,
+}
+
+impl LibraryIndex {
+    /// Looks a mapping up by label, hash or unambiguous hash prefix.
+    fn find(&self, name: &str) -> Result<usize, AppError> {
+        let name = name.trim();
+        if let Some(index) = self
+            .mappings
+            .iter()
+            .position(|m| m.label == name || m.hash == name)
+        {
+            return Ok(index);
+        }
+        let prefixed: Vec<usize> = (0..self.mappings.len())
+            .filter(|&i| name.len() >= MIN_HASH_PREFIX && self.mappings[i].hash.starts_with(name))
+            .collect();
+        match prefixed.as_slice() {
+            [index] => Ok(*index),
+            [] => Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                format!("No mapping named \"{}\" in the library", name),
+            )),
+            _ => Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                format!("\"{}\" matches more than one mapping", name),
+            )),
+        }
+    }
+
+    fn check_label(&self, label: &str, hash: &str) -> Result<(), AppError> {
+        if label.is_empty() {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                "Mapping labels cannot be empty",
+            ));
+        }
+        if self
+            .mappings
+            .iter()
+            .any(|m| m.label == label && m.hash != hash)
+        {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                format!("A mapping labelled \"{}\" already exists", label),
+            ));
+        }
+        Ok(())
+    }
+
+    /// `base`, or `base (2)`, `base (3)`... if a mapping already has it.
+    fn unused_label(&self, base: &str) -> String {
+        let base = base.trim();
+        let base = if base.is_empty() { DEFAULT_LABEL } else { base };
+        (1..)
+            .map(|n| match n {
+                1 => base.to_string(),
+                n => format!("{} ({})", base, n),
+            })
+            .find(|label| !self.mappings.iter().any(|m| &m.label == label))
+            .unwrap_or_default()
+    }
+}
+
+/// A library mapping with what its header says.
+#[derive(Debug, Serialize, Clone)]
+pub struct MappingInfo {
+    #[serde(flatten)]
+    pub entry: MappingEntry,
+    pub path: String,
+    /// Whether the current settings convert with it.
+    pub active: bool,
+    /// `None` if the file is gone or cannot be inspected natively; `error`
+    /// says why.
+    pub summary: Option<UsmapSummary>,
+    pub error: Option<String>,
+}
+
+impl MappingInfo {
+    fn new(entry: MappingEntry, active: Option<&str>) -> Self {
+        let path = stored_path(&entry.hash);
+        let (summary, error) = match Usmap::from_path(&path) {
+            Ok(usmap) => (Some(usmap.summary(&path)), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            active: active == Some(entry.hash.as_str()),
+            entry,
+            path: path.to_string_lossy().to_string(),
+            summary,
+            error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MappingImport {
+    pub mapping: MappingInfo,
+    /// The library already had this file; it kept its label.
+    pub duplicate: bool,
+}
+
+pub fn library_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rivals-vfx-editor")
+        .join("mappings")
+}
+
+/// Where the mapping with `hash` is kept. It may not exist.
+pub fn stored_path(hash: &str) -> PathBuf {
+    library_dir().join(format!("{}.usmap", hash))
+}
+
+fn read_index() -> Result<LibraryIndex, AppError> {
+    let path = library_dir().join(INDEX_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            AppError::new(
+                ErrorCode::InvalidSetting,
+                format!("Mappings library is unreadable: {}", e),
+            )
+            .with_path(&path)
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(LibraryIndex::default()),
+        Err(e) => Err(AppError::io(&path, e)),
+    }
+}
+
+/// Runs `edit` on the index and saves it, holding the library lock so two
+/// editors cannot lose each other's imports.
+fn edit_index<T>(
+    edit: impl FnOnce(&mut LibraryIndex) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let dir = library_dir();
+    let lock_path = dir.join("library.lock");
+    let _lock = LockFile::acquire(&lock_path).map_err(|e| AppError::io(&lock_path, e))?;
+    let mut index = read_index()?;
+    let result = edit(&mut index)?;
+    let path = dir.join(INDEX_FILE);
+    let json = serde_json::to_string_pretty(&index).map_err(|e| AppError::io(&path, e))?;
+    persist::write_atomic(&path, json.as_bytes()).map_err(|e| AppError::io(&path, e))?;
+    Ok(result)
+}
+
+pub fn list(active: Option<&str>) -> Result<Vec<MappingInfo>, AppError> {
+    Ok(read_index()?
+        .mappings
+        .into_iter()
+        .map(|entry| MappingInfo::new(entry, active))
+        .collect())
+}
+
+/// Rejects files that are not mappings, or that UAssetCLI cannot load,
+/// before a conversion trips over them.
+pub fn validate(path: &Path) -> Result<(), AppError> {
+    let data = fs::read(path).map_err(|e| AppError::io(path, e))?;
+    validate_data(path, &data)
+}
+
+fn validate_data(path: &Path, data: &[u8]) -> Result<(), AppError> {
+    // Oodle payloads can't be decoded here, but UAssetCLI can load them
+    match Usmap::parse(data).and_then(|usmap| usmap.check_loadable()) {
+        Ok(()) | Err(UsmapError::UnsupportedCompression(_)) => Ok(()),
+        Err(e) => Err(AppError::usmap(path, e)),
+    }
+}
+
+/// Copies `source` into the library. A file the library already has is not
+/// stored twice. Without a label the file name is used, numbered if another
+/// mapping already has it.
+pub fn import(
+    source: &Path,
+    label: Option<&str>,
+    active: Option<&str>,
+) -> Result<MappingImport, AppError> {
+    let data = fs::read(source).map_err(|e| AppError::io(source, e))?;
+    validate_data(source, &data)?;
+    let hash = hex::encode(Sha256::digest(&data));
+    let file_name = source
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let stem = source
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let (entry, duplicate) = edit_index(|index| {
+        if let Some(existing) = index.mappings.iter().find(|m| m.hash == hash) {
+            return Ok((existing.clone(), true));
+        }
+        let label = match label.map(str::trim) {
+            Some(label) => label.to_string(),
+            None => index.unused_label(&stem),
+        };
+        index.check_label(&label, &hash)?;
+        let path = stored_path(&hash);
+        persist::write_atomic(&path, &data).map_err(|e| AppError::io(&path, e))?;
+        let entry = MappingEntry {
+            hash: hash.clone(),
+            label,
+            file_name,
+            imported_at: chrono::Utc::now().to_rfc3339(),
+            size: data.len() as u64,
+        };
+        index.mappings.push(entry.clone());
+        Ok((entry, false))
+    })?;
+    eprintln!(
+        "[DEBUG] Mapping {:?} {} as \"{}\" ({})",
+        source,
+        if duplicate {
+            "already imported"
+        } else {
+            "imported"
+        },
+        entry.label,
+        entry.hash
+    );
+    Ok(MappingImport {
+        mapping: MappingInfo::new(entry, active),
+        duplicate,
+    })
+}
+
+/// The library entry `name` refers to, by label, hash or hash prefix.
+pub fn resolve(name: &str) -> Result<MappingEntry, AppError> {
+    let index = read_index()?;
+    let position = index.find(name)?;
+    Ok(index.mappings[position].clone())
+}
+
+pub fn relabel(name: &str, label: &str) -> Result<MappingEntry, AppError> {
+    let label = label.trim();
+    edit_index(|index| {
+        let position = index.find(name)?;
+        let hash = index.mappings[position].hash.clone();
+        index.check_label(label, &hash)?;
+        index.mappings[position].label = label.to_string();
+        Ok(index.mappings[position].clone())
+    })
+}
+
+/// Deletes a mapping and its file. Projects that still name it report it as
+/// missing when they convert.
+pub fn remove(name: &str) -> Result<MappingEntry, AppError> {
+    let entry = edit_index(|index| {
+        let position = index.find(name)?;
+        Ok(index.mappings.remove(position))
+    })?;
+    let path = stored_path(&entry.hash);
+    if let Err(e) = fs::remove_file(&path) {
+        eprintln!("[DEBUG] Failed to delete mapping {:?}: {}", path, e);
+    }
+    Ok(entry)
+}
+
+/// Copies a mapping to where UAssetGUI loads its `Rivals` mapping from, for
+/// the batch scripts and opening assets in UAssetGUI by hand.
+pub fn install_for_uassetgui(name: &str) -> Result<String, AppError> {
+    let entry = resolve(name)?;
+    let source = stored_path(&entry.hash);
+    let target = dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("UAssetGUI")
+        .join("Mappings")
+        .join(UASSETGUI_MAPPING);
+    let data = fs::read(&source).map_err(|e| AppError::io(&source, e))?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    persist::write_atomic(&target, &data).map_err(|e| AppError::io(&target, e))?;
+    eprintln!(
+        "[DEBUG] Installed mapping \"{}\" for UAssetGUI at {:?}",
+        entry.label, target
+    );
+    Ok(target.to_string_lossy().to_string())
+}
@@ -0,0 +1,286 @@
+//! Settings file versioning.
+//!
+//! `settings.json` records the schema `version` it was written with. Files
+//! from older editors are brought forward one step at a time, then read
+//! field by field so one bad value does not cost the user the rest.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::profiles::DEFAULT_PROFILE;
+use crate::AppSettings;
+
+pub const SETTINGS_VERSION: u32 = 2;
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a version `n` file to version `n + 1`.
+const MIGRATIONS: &[Migration] = &[v0_detailed_errors, v1_filter_profiles];
+
+/// Version 0 predates the `version` field. Its detailed errors toggle was
+/// removed from the UI and the editor forced it on at every load; store
+/// that instead, so the setting can be changed again.
+fn v0_detailed_errors(settings: &mut Map<String, Value>) {
+    settings.insert("showDetailedErrors".to_string(), Value::Bool(true));
+}
+
+/// Version 1 had a single `filterDictionary`; it becomes the default profile.
+fn v1_filter_profiles(settings: &mut Map<String, Value>) {
+    if let Some(dictionary) = settings.remove("filterDictionary") {
+        settings.insert(
+            "filterProfiles".to_string(),
+            json!({
+                "active": DEFAULT_PROFILE,
+                "profiles": { DEFAULT_PROFILE: dictionary },
+            }),
+        );
+    }
+}
+
+pub struct LoadedSettings {
+    pub settings: AppSettings,
+    /// Version the file had, if it was older than `SETTINGS_VERSION`.
+    pub migrated_from: Option<u32>,
+    /// Fields whose value could not be read and were reset to the default.
+    pub dropped: Vec<String>,
+}
+
+/// Reads a settings file, migrating it to the current version. Fails only
+/// if the file is not a JSON object at all.
+pub fn parse_settings(content: &str) -> Result<LoadedSettings, String> {
+    let Value::Object(mut fields) = serde_json::from_str(content).map_err(|e| e.to_string())?
+    else {
+        return Err("settings file is not a JSON object".to_string());
+    };
+
+    let version = file_version(&fields);
+    if version > SETTINGS_VERSION {
+        eprintln!(
+            "[DEBUG] Settings were written by a newer editor (version {}), reading what is known",
+            version
+        );
+    }
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(&mut fields);
+    }
+    let migrated_from = (version < SETTINGS_VERSION).then_some(version);
+    if migrated_from.is_some() {
+        fields.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+    }
+
+    let (settings, dropped) = overlay(&AppSettings::default(), fields)?;
+    Ok(LoadedSettings {
+        settings,
+        migrated_from,
+        dropped,
+    })
+}
+
+fn file_version(fields: &Map<String, Value>) -> u32 {
+    fields
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v as u32)
+}
+
+/// Refuses to write over a settings file from a newer editor: it may hold
+/// fields this one does not know, and saving would drop them. Anything
+/// that is not a settings object has already been backed up on load.
+pub fn check_overwrite(existing: &str) -> Result<(), String> {
+    let Ok(Value::Object(fields)) = serde_json::from_str(existing) else {
+        return Ok(());
+    };
+    match file_version(&fields) {
+        version if version > SETTINGS_VERSION => Err(format!(
+            "Settings were written by a newer version of the editor (settings version {}); \
+             update the editor to change them",
+            version
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Copies a settings file that could not be fully read next to it, before
+/// defaults get written over it, and returns where the copy went.
+pub fn backup_settings(path: &Path) -> io::Result<PathBuf> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let backup = (0..)
+        .map(|n| match n {
+            0 => path.with_file_name(format!("settings.{}.bak.json", stamp)),
+            n => path.with_file_name(format!("settings.{}-{}.bak.json", stamp, n)),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_default();
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// Takes over each of `fields` onto `base` that still parses, and returns
+/// the result with the names of the fields that did not.
+pub fn overlay(
+    base: &AppSettings,
+    fields: Map<String, Value>,
+) -> Result<(AppSettings, Vec<String>), String> {
+    let Ok(Value::Object(mut merged)) = serde_json::to_value(base) else {
+        return Err("settings are not a JSON object".to_string());
+    };
+    let mut dropped = Vec::new();
+    for (key, value) in fields {
+        let previous = merged.insert(key.clone(), value);
+        if serde_json::from_value::<AppSettings>(Value::Object(merged.clone())).is_err() {
+            match previous {
+                Some(previous) => merged.insert(key.clone(), previous),
+                None => merged.remove(&key),
+            };
+            dropped.push(key);
+        }
+    }
+
+    let settings = serde_json::from_value(Value::Object(merged)).map_err(|e| e.to_string())?;
+    Ok((settings, dropped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_version_0_to_current() {
+        let loaded = parse_settings(
+            r#"{
+                "showDetailedErrors": false,
+                "usmapPath": "C:/Mappings.usmap",
+                "filterDictionary": {
+                    "include_keywords": ["Glow"],
+                    "exclude_keywords": [],
+                    "color_property_names": ["Color"]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(loaded.migrated_from, Some(0));
+        assert!(loaded.dropped.is_empty());
+        assert_eq!(loaded.settings.version, SETTINGS_VERSION);
+        assert!(loaded.settings.show_detailed_errors);
+        assert_eq!(
+            loaded.settings.usmap_path.as_deref(),
+            Some("C:/Mappings.usmap")
+        );
+        let profiles = &loaded.settings.filter_profiles;
+        assert_eq!(profiles.active, DEFAULT_PROFILE);
+        assert_eq!(
+            profiles.profiles[DEFAULT_PROFILE].include_keywords,
+            ["Glow"]
+        );
+    }
+
+    #[test]
+    fn moves_filter_dictionary_into_default_profile() {
+        let loaded = parse_settings(
+            r#"{
+                "version": 1,
+                "showDetailedErrors": false,
+                "filterDictionary": {
+                    "include_keywords": ["Tint"],
+                    "exclude_keywords": ["Debug"],
+                    "color_property_names": []
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(loaded.migrated_from, Some(1));
+        // Only version 0 files get detailed errors forced on
+        assert!(!loaded.settings.show_detailed_errors);
+        let profiles = &loaded.settings.filter_profiles;
+        assert_eq!(profiles.active, DEFAULT_PROFILE);
+        assert_eq!(profiles.profiles.len(), 1);
+        let dictionary = &profiles.profiles[DEFAULT_PROFILE];
+        assert_eq!(dictionary.include_keywords, ["Tint"]);
+        assert_eq!(dictionary.exclude_keywords, ["Debug"]);
+    }
+
+    #[test]
+    fn drops_only_unreadable_fields() {
+        let loaded = parse_settings(
+            r#"{
+                "version": 2,
+                "conversionConcurrency": "many",
+                "usmapPath": "C:/Mappings.usmap",
+                "showDetailedErrors": false
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(loaded.migrated_from, None);
+        assert_eq!(loaded.dropped, ["conversionConcurrency"]);
+        assert_eq!(
+            loaded.settings.conversion_concurrency,
+            AppSettings::default().conversion_concurrency
+        );
+        assert_eq!(
+            loaded.settings.usmap_path.as_deref(),
+            Some("C:/Mappings.usmap")
+        );
+        assert!(!loaded.settings.show_detailed_errors);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_objects() {
+        assert!(parse_settings("[1, 2]").is_err());
+        assert!(parse_settings("{ not json").is_err());
+    }
+
+    #[test]
+    fn backs_up_without_replacing_earlier_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"version": 2, "conversionConcurrency": "many"}"#).unwrap();
+
+        let first = backup_settings(&path).unwrap();
+        let second = backup_settings(&path).unwrap();
+
+        assert_ne!(first, second);
+        for backup in [&first, &second] {
+            assert_eq!(backup.parent(), Some(dir.path()));
+            assert_eq!(
+                fs::read_to_string(backup).unwrap(),
+                fs::read_to_string(&path).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn reads_known_fields_from_newer_files() {
+        let loaded = parse_settings(
+            r#"{
+                "version": 3,
+                "usmapPath": "C:/Mappings.usmap",
+                "someFutureSetting": true
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(loaded.migrated_from, None);
+        assert!(loaded.dropped.is_empty());
+        assert_eq!(
+            loaded.settings.usmap_path.as_deref(),
+            Some("C:/Mappings.usmap")
+        );
+    }
+
+    #[test]
+    fn refuses_to_overwrite_newer_files() {
+        let newer = r#"{"version": 3, "someFutureSetting": true}"#;
+        let error = check_overwrite(newer).unwrap_err();
+        assert!(error.contains("settings version 3"));
+
+        assert!(check_overwrite(r#"{"version": 2}"#).is_ok());
+        assert!(check_overwrite(r#"{"showDetailedErrors": true}"#).is_ok());
+        assert!(check_overwrite("{ not json").is_ok());
+    }
+}
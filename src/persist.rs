@@ -0,0 +1,48 @@
+//! Crash- and multi-instance-safe file writes for settings and the cache index.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Replaces `path` with `contents` so readers see either the old or the new
+/// file, never a partial one: the data goes to a temp file next to it, is
+/// flushed to disk and then renamed over the original.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut temp = tempfile::Builder::new()
+        .prefix(&format!(".{}.", file_name))
+        .suffix(".tmp")
+        .tempfile_in(dir)?;
+    temp.write_all(contents)?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Advisory lock shared by every editor instance, held from reading a file
+/// to replacing it. The OS drops it with the process, so a crash cannot
+/// leave it stuck.
+pub struct LockFile(File);
+
+impl LockFile {
+    /// Blocks until no other instance holds the lock at `path`.
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        file.lock()?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
@@ -0,0 +1,190 @@
+//! Named filter dictionary profiles.
+//!
+//! Ability VFX, RichText tables and hero skins want different keywords, so
+//! settings keep several named dictionaries with one of them active. A
+//! profile on disk has the same shape as `RVFXE/filter_dictionary.json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::error::{AppError, ErrorCode};
+use crate::filter::FilterMatcher;
+use crate::persist;
+use crate::{watch, FilterDictionary};
+
+pub const DEFAULT_PROFILE: &str = "Default";
+
+static DEFAULT_DICTIONARY: LazyLock<FilterDictionary> = LazyLock::new(FilterDictionary::default);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterProfiles {
+    /// Name of the profile conversions and the editor filter with.
+    pub active: String,
+    pub profiles: BTreeMap<String, FilterDictionary>,
+    /// Profiles kept in sync with a dictionary file, by profile name. The
+    /// file is re-read when it changes and edits are written back to it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub links: BTreeMap<String, String>,
+}
+
+impl Default for FilterProfiles {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), FilterDictionary::default())]),
+            links: BTreeMap::new(),
+        }
+    }
+}
+
+impl FilterProfiles {
+    /// The active dictionary, or the built-in one if a hand-edited settings
+    /// file names a profile that does not exist.
+    pub fn active(&self) -> &FilterDictionary {
+        self.profiles
+            .get(&self.active)
+            .unwrap_or(&DEFAULT_DICTIONARY)
+    }
+
+    pub fn set_active(&mut self, dictionary: FilterDictionary) {
+        self.profiles.insert(self.active.clone(), dictionary);
+    }
+
+    pub fn get(&self, name: &str) -> Result<&FilterDictionary, AppError> {
+        self.profiles.get(name).ok_or_else(|| unknown(name))
+    }
+
+    /// Adds a profile and makes it active.
+    pub fn create(&mut self, name: &str, dictionary: FilterDictionary) -> Result<(), AppError> {
+        let name = self.new_name(name)?;
+        self.profiles.insert(name.clone(), dictionary);
+        self.active = name;
+        Ok(())
+    }
+
+    /// Copies `name` to `new_name` and makes the copy active.
+    pub fn duplicate(&mut self, name: &str, new_name: &str) -> Result<(), AppError> {
+        let dictionary = self.get(name)?.clone();
+        self.create(new_name, dictionary)
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<(), AppError> {
+        let new_name = self.new_name(new_name)?;
+        let dictionary = self.profiles.remove(name).ok_or_else(|| unknown(name))?;
+        self.profiles.insert(new_name.clone(), dictionary);
+        if let Some(link) = self.links.remove(name) {
+            self.links.insert(new_name.clone(), link);
+        }
+        if self.active == name {
+            self.active = new_name;
+        }
+        Ok(())
+    }
+
+    pub fn switch(&mut self, name: &str) -> Result<(), AppError> {
+        self.get(name)?;
+        self.active = name.to_string();
+        Ok(())
+    }
+
+    /// Removes a profile. The last one cannot be deleted; if the active one
+    /// is, the first remaining profile becomes active.
+    pub fn delete(&mut self, name: &str) -> Result<(), AppError> {
+        self.get(name)?;
+        if self.profiles.len() == 1 {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                "The last filter profile cannot be deleted",
+            ));
+        }
+        self.profiles.remove(name);
+        self.links.remove(name);
+        if self.active == name {
+            self.active = self.profiles.keys().next().cloned().unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// The dictionary file the active profile is linked to, if any.
+    pub fn active_link(&self) -> Option<&Path> {
+        self.links.get(&self.active).map(Path::new)
+    }
+
+    /// Fails if a profile has a rule that does not compile.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for (name, dictionary) in &self.profiles {
+            FilterMatcher::new(dictionary).map_err(|e| AppError {
+                message: format!("Filter profile \"{}\": {}", name, e.message),
+                ..e
+            })?;
+        }
+        Ok(())
+    }
+
+    /// `base`, or `base (2)`, `base (3)`... whichever is not taken yet.
+    pub fn unused_name(&self, base: &str) -> String {
+        let base = base.trim();
+        let base = if base.is_empty() {
+            DEFAULT_PROFILE
+        } else {
+            base
+        };
+        (1..)
+            .map(|n| match n {
+                1 => base.to_string(),
+                n => format!("{} ({})", base, n),
+            })
+            .find(|name| !self.profiles.contains_key(name))
+            .unwrap_or_default()
+    }
+
+    fn new_name(&self, name: &str) -> Result<String, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                "Filter profile names cannot be empty",
+            ));
+        }
+        if self.profiles.contains_key(name) {
+            return Err(AppError::new(
+                ErrorCode::InvalidSetting,
+                format!("A filter profile named \"{}\" already exists", name),
+            ));
+        }
+        Ok(name.to_string())
+    }
+}
+
+fn unknown(name: &str) -> AppError {
+    AppError::new(
+        ErrorCode::InvalidSetting,
+        format!("No filter profile named \"{}\"", name),
+    )
+}
+
+/// Reads a standalone dictionary file. Missing lists fall back to the
+/// built-in keywords, like they do in settings.
+pub fn read_profile(path: &Path) -> Result<FilterDictionary, AppError> {
+    let content = fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    let dictionary = serde_json::from_str(&content).map_err(|e| {
+        AppError::new(
+            ErrorCode::InvalidSetting,
+            format!("Not a filter dictionary: {}", e),
+        )
+        .with_path(path)
+    })?;
+    FilterMatcher::new(&dictionary).map_err(|e| e.with_path(path))?;
+    Ok(dictionary)
+}
+
+pub fn write_profile(dictionary: &FilterDictionary, path: &Path) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(dictionary).map_err(|e| AppError::io(path, e))?;
+    persist::write_atomic(path, json.as_bytes()).map_err(|e| AppError::io(path, e))?;
+    watch::record_own_write(path);
+    Ok(())
+}
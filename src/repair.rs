@@ -0,0 +1,199 @@
+//! Cache integrity scan and repair.
+//!
+//! Reconciles `index.json` with the JSON files actually in the cache: entries
+//! without a file are dropped, unreadable files are deleted, and files the
+//! index lost track of are re-adopted when it is safe to, otherwise deleted.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::{compute_file_hash, CacheEntry, CacheIndex, CacheKey, FileStat, Fingerprint};
+
+/// Where `load_cache_index` sets aside an index it could not parse.
+pub const CORRUPT_INDEX_NAME: &str = "index.corrupt.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CacheRepairReport {
+    /// Source assets whose entry was dropped because its JSON is gone.
+    pub missing_json: Vec<String>,
+    /// JSON files that did not parse and were deleted.
+    pub unreadable: Vec<String>,
+    /// JSON files no entry pointed at that were added back to the index.
+    pub rebuilt: Vec<String>,
+    /// JSON files no entry pointed at that could not be matched to a current
+    /// source asset and were deleted.
+    pub orphans_removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// What a rebuilt entry is keyed with and when its inputs last changed.
+pub struct RepairInputs<'a> {
+    pub key: &'a CacheKey,
+    /// Newest modification time of the usmap and UAssetCLI. JSON older than
+    /// this may have been produced with different inputs.
+    pub inputs_modified: Option<SystemTime>,
+}
+
+pub fn repair(
+    cache_dir: &Path,
+    index: &mut CacheIndex,
+    inputs: &RepairInputs,
+) -> CacheRepairReport {
+    let mut report = CacheRepairReport::default();
+
+    // Unreadable files first, so their entries count as missing below
+    let mut files = Vec::new();
+    collect_json_files(cache_dir, cache_dir, &mut files);
+    files.retain(|path| {
+        if is_valid_json(path) {
+            return true;
+        }
+        report.freed_bytes += remove(path);
+        report.unreadable.push(path.to_string_lossy().to_string());
+        false
+    });
+
+    index.entries.retain(|uasset_path, entry| {
+        let exists = Path::new(&entry.json_path).is_file();
+        if !exists {
+            report.missing_json.push(uasset_path.clone());
+        }
+        exists
+    });
+    // Unclaimed bundle imports are only a fallback, drop them quietly
+    for imported in index.imported.values_mut() {
+        imported.retain(|entry| Path::new(&entry.json_path).is_file());
+    }
+    index.imported.retain(|_, imported| !imported.is_empty());
+
+    let referenced: HashSet<PathBuf> = index
+        .entries
+        .values()
+        .map(|entry| PathBuf::from(&entry.json_path))
+        .chain(
+            index
+                .imported
+                .values()
+                .flatten()
+                .map(|entry| PathBuf::from(&entry.json_path)),
+        )
+        .collect();
+    let salvaged = read_salvage(cache_dir);
+
+    for path in files.into_iter().filter(|p| !referenced.contains(p)) {
+        let source = salvaged
+            .get(&path)
+            .filter(|uasset| can_adopt(&path, uasset, inputs))
+            .and_then(|uasset| {
+                let stat = FileStat::of(Path::new(uasset));
+                let hash = compute_file_hash(Path::new(uasset)).ok()?;
+                Some((uasset, Fingerprint { hash, stat }))
+            });
+        match source {
+            Some((uasset_path, fingerprint)) => {
+                let json_path = path.to_string_lossy().to_string();
+                index.entries.insert(
+                    uasset_path.clone(),
+                    CacheEntry::new(fingerprint, &json_path, uasset_path, inputs.key),
+                );
+                report.rebuilt.push(json_path);
+            }
+            None => {
+                report.freed_bytes += remove(&path);
+                report
+                    .orphans_removed
+                    .push(path.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    report
+}
+
+/// Cached JSON files below `dir`, without the index files at the top.
+fn collect_json_files(cache_dir: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_json_files(cache_dir, &path, files);
+        } else if path.extension().is_some_and(|e| e == "json")
+            && !(dir == cache_dir
+                && (entry.file_name() == "index.json" || entry.file_name() == CORRUPT_INDEX_NAME))
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Parses without building a value, so multi-GB files stay cheap.
+fn is_valid_json(path: &Path) -> bool {
+    fs::File::open(path)
+        .map(|file| {
+            serde_json::from_reader::<_, serde::de::IgnoredAny>(BufReader::new(file)).is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// A lost JSON is only trusted if nothing it was produced from changed since.
+fn can_adopt(json_path: &Path, uasset_path: &str, inputs: &RepairInputs) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let (Some(json_time), Some(uasset_time)) =
+        (modified(json_path), modified(Path::new(uasset_path)))
+    else {
+        return false;
+    };
+    uasset_time <= json_time && inputs.inputs_modified.is_none_or(|t| t <= json_time)
+}
+
+/// JSON path to source asset pairs recovered from a set-aside corrupt index.
+fn read_salvage(cache_dir: &Path) -> HashMap<PathBuf, String> {
+    fs::read_to_string(cache_dir.join(CORRUPT_INDEX_NAME))
+        .map(|text| salvage_entries(&text))
+        .unwrap_or_default()
+}
+
+/// Recovers entries from an index that no longer parses as a whole, e.g.
+/// one cut off mid-write. Relies on the pretty-printed layout of
+/// `save_cache_index`, one field per line.
+fn salvage_entries(text: &str) -> HashMap<PathBuf, String> {
+    let mut pairs = HashMap::new();
+    let mut json_path = None;
+    let mut uasset_path = None;
+
+    for line in text.lines() {
+        let Some((field, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_end_matches(',');
+        let Ok(value) = serde_json::from_str::<String>(value) else {
+            continue;
+        };
+        match field.trim() {
+            "\"json_path\"" => json_path = Some(value),
+            "\"uasset_path\"" => uasset_path = Some(value),
+            _ => continue,
+        }
+        if let (Some(json), Some(uasset)) = (&json_path, &uasset_path) {
+            pairs.insert(PathBuf::from(json), uasset.clone());
+            json_path = None;
+            uasset_path = None;
+        }
+    }
+
+    pairs
+}
+
+fn remove(path: &Path) -> u64 {
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    match fs::remove_file(path) {
+        Ok(()) => size,
+        Err(_) => 0,
+    }
+}
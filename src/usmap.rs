@@ -0,0 +1,789 @@
+//! Native reader for `.usmap` mapping files.
+//!
+//! Mirrors `UAssetAPI/Unversioned/Usmap.cs` closely enough to validate a
+//! mapping before it is handed to UAssetCLI. Extension blocks (CEXT/PPTH)
+//! after the struct table are not needed for that and are skipped.
+
+use serde::Serialize;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+pub const USMAP_MAGIC: u16 = 0x30C4;
+
+/// Largest payload a compressed mapping may claim. Real game mappings are a
+/// few dozen MB; the header is untrusted, so this bounds what it can allocate.
+const MAX_DECOMPRESSED_SIZE: u32 = 256 * 1024 * 1024;
+
+/// Deepest type nesting a property may have, e.g. a map of arrays of enums.
+const MAX_PROPERTY_DEPTH: usize = 16;
+
+/// Structs the editor relies on; a mapping without them cannot produce
+/// usable material or RichText JSON.
+pub const KEY_STRUCTS: &[&str] = &[
+    "LinearColor",
+    "MaterialInstanceConstant",
+    "VectorParameterValue",
+    "MaterialParameterInfo",
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UsmapVersion {
+    Initial,
+    PackageVersioning,
+    LongFName,
+    LargeEnums,
+    ExplicitEnumValues,
+}
+
+impl UsmapVersion {
+    fn from_byte(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Initial),
+            1 => Some(Self::PackageVersioning),
+            2 => Some(Self::LongFName),
+            3 => Some(Self::LargeEnums),
+            4 => Some(Self::ExplicitEnumValues),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMethod {
+    None,
+    Oodle,
+    Brotli,
+    ZStandard,
+}
+
+#[derive(Debug)]
+pub enum UsmapError {
+    Io(String),
+    /// The file is not a usmap or its tables are truncated or inconsistent.
+    Format(String),
+    /// Valid header, but the payload uses a codec we cannot decode natively.
+    /// UAssetCLI may still be able to load it.
+    UnsupportedCompression(CompressionMethod),
+}
+
+impl fmt::Display for UsmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsmapError::Io(e) => write!(f, ".usmap: {}", e),
+            UsmapError::Format(e) => write!(f, ".usmap: {}", e),
+            UsmapError::UnsupportedCompression(method) => write!(
+                f,
+                ".usmap: {:?} compression cannot be inspected natively",
+                method
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersioning {
+    pub file_version_ue4: i32,
+    pub file_version_ue5: i32,
+    pub custom_version_count: usize,
+    pub net_cl: u32,
+}
+
+#[derive(Debug, Clone)]
+pub enum PropertyData {
+    Simple(u8),
+    Enum {
+        inner: Box<PropertyData>,
+        name: Option<String>,
+    },
+    Struct {
+        struct_type: Option<String>,
+    },
+    /// ArrayProperty, SetProperty and OptionalProperty share this layout.
+    Container {
+        type_id: u8,
+        inner: Box<PropertyData>,
+    },
+    Map {
+        key: Box<PropertyData>,
+        value: Box<PropertyData>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct UsmapProperty {
+    pub name: Option<String>,
+    pub schema_index: u16,
+    pub array_size: u8,
+    pub data: PropertyData,
+}
+
+#[derive(Debug, Clone)]
+pub struct UsmapStruct {
+    pub name: Option<String>,
+    pub super_name: Option<String>,
+    pub property_count: u16,
+    pub properties: Vec<UsmapProperty>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UsmapEnum {
+    pub name: Option<String>,
+    pub values: Vec<(i64, Option<String>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UsmapHeader {
+    pub version: UsmapVersion,
+    pub package_versioning: Option<PackageVersioning>,
+    pub compression: CompressionMethod,
+    pub compressed_size: u32,
+    pub decompressed_size: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Usmap {
+    pub header: UsmapHeader,
+    pub names: Vec<String>,
+    pub enums: Vec<UsmapEnum>,
+    pub structs: Vec<UsmapStruct>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyStructPresence {
+    pub name: String,
+    pub present: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UsmapSummary {
+    pub path: String,
+    pub version: UsmapVersion,
+    pub version_number: u8,
+    pub compression: CompressionMethod,
+    pub package_versioning: Option<PackageVersioning>,
+    pub name_count: usize,
+    pub enum_count: usize,
+    pub struct_count: usize,
+    pub key_structs: Vec<KeyStructPresence>,
+}
+
+impl Usmap {
+    pub fn from_path(path: &Path) -> Result<Self, UsmapError> {
+        let data = std::fs::read(path).map_err(|e| UsmapError::Io(e.to_string()))?;
+        Self::parse(&data)
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self, UsmapError> {
+        let mut reader = ByteReader::new(data);
+        let header = read_header(&mut reader)?;
+        let remaining = &data[reader.pos..];
+        if header.decompressed_size > MAX_DECOMPRESSED_SIZE {
+            return Err(UsmapError::Format(format!(
+                "Decompressed size {} is larger than the {} byte limit",
+                header.decompressed_size, MAX_DECOMPRESSED_SIZE
+            )));
+        }
+
+        let payload = match header.compression {
+            CompressionMethod::None => {
+                if header.compressed_size != header.decompressed_size {
+                    return Err(UsmapError::Format(
+                        "Compressed size must be equal to decompressed size".to_string(),
+                    ));
+                }
+                take_compressed(remaining, header.compressed_size)?.to_vec()
+            }
+            CompressionMethod::Brotli => {
+                let compressed = take_compressed(remaining, header.compressed_size)?;
+                let mut out = Vec::with_capacity(header.decompressed_size as usize);
+                // One byte past the size is enough to tell the header lied
+                brotli_decompressor::Decompressor::new(compressed, 4096)
+                    .take(header.decompressed_size as u64 + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|e| UsmapError::Format(format!("Brotli: {}", e)))?;
+                out
+            }
+            CompressionMethod::ZStandard => {
+                let compressed = take_compressed(remaining, header.compressed_size)?;
+                zstd::bulk::decompress(compressed, header.decompressed_size as usize)
+                    .map_err(|e| UsmapError::Format(format!("ZStandard: {}", e)))?
+            }
+            CompressionMethod::Oodle => {
+                return Err(UsmapError::UnsupportedCompression(header.compression))
+            }
+        };
+
+        if payload.len() != header.decompressed_size as usize {
+            return Err(UsmapError::Format(format!(
+                "Decompressed {} bytes, header says {}",
+                payload.len(),
+                header.decompressed_size
+            )));
+        }
+
+        read_tables(header, &payload)
+    }
+
+    /// Fails for mappings this reader understands but the bundled UAssetAPI
+    /// cannot load, so they are turned away before every conversion fails.
+    pub fn check_loadable(&self) -> Result<(), UsmapError> {
+        if self.header.compression == CompressionMethod::Brotli {
+            return Err(UsmapError::Format(
+                "Brotli compressed mappings cannot be loaded by UAssetCLI, export it with ZStandard, Oodle or no compression".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn has_struct(&self, name: &str) -> bool {
+        // FNames are case-insensitive, like Usmap.AreFNamesCaseInsensitive
+        self.structs.iter().any(|s| {
+            s.name
+                .as_deref()
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        })
+    }
+
+    pub fn summary(&self, path: &Path) -> UsmapSummary {
+        UsmapSummary {
+            path: path.to_string_lossy().to_string(),
+            version: self.header.version,
+            version_number: self.header.version as u8,
+            compression: self.header.compression,
+            package_versioning: self.header.package_versioning.clone(),
+            name_count: self.names.len(),
+            enum_count: self.enums.len(),
+            struct_count: self.structs.len(),
+            key_structs: KEY_STRUCTS
+                .iter()
+                .map(|name| KeyStructPresence {
+                    name: name.to_string(),
+                    present: self.has_struct(name),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn take_compressed(remaining: &[u8], compressed_size: u32) -> Result<&[u8], UsmapError> {
+    remaining
+        .get(..compressed_size as usize)
+        .ok_or_else(|| UsmapError::Format("Compressed payload is truncated".to_string()))
+}
+
+fn read_header(reader: &mut ByteReader) -> Result<UsmapHeader, UsmapError> {
+    if reader.u16()? != USMAP_MAGIC {
+        return Err(UsmapError::Format("File signature mismatch".to_string()));
+    }
+
+    let version_byte = reader.u8()?;
+    let version = UsmapVersion::from_byte(version_byte)
+        .ok_or_else(|| UsmapError::Format(format!("Unknown file version {}", version_byte)))?;
+
+    let mut package_versioning = None;
+    if version >= UsmapVersion::PackageVersioning && reader.i32()? > 0 {
+        let file_version_ue4 = reader.i32()?;
+        let file_version_ue5 = reader.i32()?;
+        let custom_version_count = reader.count()?;
+        for _ in 0..custom_version_count {
+            reader.bytes(16)?; // guid
+            reader.i32()?; // version number
+        }
+        package_versioning = Some(PackageVersioning {
+            file_version_ue4,
+            file_version_ue5,
+            custom_version_count,
+            net_cl: reader.u32()?,
+        });
+    }
+
+    let compression = match reader.u8()? {
+        0 => CompressionMethod::None,
+        1 => CompressionMethod::Oodle,
+        2 => CompressionMethod::Brotli,
+        3 => CompressionMethod::ZStandard,
+        other => {
+            return Err(UsmapError::Format(format!(
+                "Compression method {} is unimplemented",
+                other
+            )))
+        }
+    };
+
+    Ok(UsmapHeader {
+        version,
+        package_versioning,
+        compression,
+        compressed_size: reader.u32()?,
+        decompressed_size: reader.u32()?,
+    })
+}
+
+fn read_tables(header: UsmapHeader, payload: &[u8]) -> Result<Usmap, UsmapError> {
+    let mut reader = ByteReader::new(payload);
+    let version = header.version;
+
+    // part 1: names
+    let name_count = reader.count()?;
+    let mut names = Vec::with_capacity(name_count);
+    for _ in 0..name_count {
+        let length = if version >= UsmapVersion::LongFName {
+            reader.u16()? as usize
+        } else {
+            reader.u8()? as usize
+        };
+        let bytes = reader.bytes(length)?;
+        names.push(String::from_utf8_lossy(bytes).to_string());
+    }
+
+    // part 2: enums
+    let enum_count = reader.count()?;
+    let mut enums = Vec::with_capacity(enum_count);
+    for _ in 0..enum_count {
+        let name = reader.name(&names)?;
+        let entry_count = if version >= UsmapVersion::LargeEnums {
+            reader.u16()? as usize
+        } else {
+            reader.u8()? as usize
+        };
+        let mut values = Vec::with_capacity(entry_count);
+        for ordinal in 0..entry_count {
+            if version >= UsmapVersion::ExplicitEnumValues {
+                let value = reader.i64()?;
+                values.push((value, reader.name(&names)?));
+            } else {
+                values.push((ordinal as i64, reader.name(&names)?));
+            }
+        }
+        enums.push(UsmapEnum { name, values });
+    }
+
+    // part 3: schemas
+    let struct_count = reader.count()?;
+    let mut structs = Vec::with_capacity(struct_count);
+    for _ in 0..struct_count {
+        let name = reader.name(&names)?;
+        let super_name = reader.name(&names)?;
+        let property_count = reader.u16()?;
+        let serializable_count = reader.u16()?;
+        let mut properties = Vec::with_capacity(serializable_count as usize);
+        for _ in 0..serializable_count {
+            let schema_index = reader.u16()?;
+            let array_size = reader.u8()?;
+            let name = reader.name(&names)?;
+            let data = read_property_data(&mut reader, &names, 0)?;
+            properties.push(UsmapProperty {
+                name,
+                schema_index,
+                array_size,
+                data,
+            });
+        }
+        structs.push(UsmapStruct {
+            name,
+            super_name,
+            property_count,
+            properties,
+        });
+    }
+
+    Ok(Usmap {
+        header,
+        names,
+        enums,
+        structs,
+    })
+}
+
+// EPropertyType values that carry nested type data
+const ARRAY_PROPERTY: u8 = 8;
+const STRUCT_PROPERTY: u8 = 9;
+const MAP_PROPERTY: u8 = 24;
+const SET_PROPERTY: u8 = 25;
+const ENUM_PROPERTY: u8 = 26;
+const OPTIONAL_PROPERTY: u8 = 28;
+
+fn read_property_data(
+    reader: &mut ByteReader,
+    names: &[String],
+    depth: usize,
+) -> Result<PropertyData, UsmapError> {
+    if depth >= MAX_PROPERTY_DEPTH {
+        return Err(UsmapError::Format(format!(
+            "Property types nested deeper than {} at offset {}",
+            MAX_PROPERTY_DEPTH, reader.pos
+        )));
+    }
+    let type_id = reader.u8()?;
+    let mut inner = || read_property_data(reader, names, depth + 1).map(Box::new);
+    Ok(match type_id {
+        ENUM_PROPERTY => {
+            let inner = inner()?;
+            PropertyData::Enum {
+                inner,
+                name: reader.name(names)?,
+            }
+        }
+        STRUCT_PROPERTY => PropertyData::Struct {
+            struct_type: reader.name(names)?,
+        },
+        ARRAY_PROPERTY | SET_PROPERTY | OPTIONAL_PROPERTY => PropertyData::Container {
+            type_id,
+            inner: inner()?,
+        },
+        MAP_PROPERTY => PropertyData::Map {
+            key: inner()?,
+            value: inner()?,
+        },
+        _ => PropertyData::Simple(type_id),
+    })
+}
+
+/// Little-endian cursor with bounds checks, like `UsmapBinaryReader`.
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], UsmapError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len());
+        let Some(end) = end else {
+            return Err(UsmapError::Format(format!(
+                "Unexpected end of data at offset {}",
+                self.pos
+            )));
+        };
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], UsmapError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, UsmapError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, UsmapError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, UsmapError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, UsmapError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, UsmapError> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    /// A non-negative element count that must fit in the remaining data.
+    fn count(&mut self) -> Result<usize, UsmapError> {
+        let value = self.i32()?;
+        if value < 0 || value as usize > self.data.len() - self.pos {
+            return Err(UsmapError::Format(format!(
+                "Invalid element count {} at offset {}",
+                value,
+                self.pos - 4
+            )));
+        }
+        Ok(value as usize)
+    }
+
+    /// Reads a name-map index; negative indices are null names.
+    fn name(&mut self, names: &[String]) -> Result<Option<String>, UsmapError> {
+        let index = self.i32()?;
+        if index < 0 {
+            return Ok(None);
+        }
+        names.get(index as usize).cloned().map(Some).ok_or_else(|| {
+            UsmapError::Format(format!(
+                "Name index {} out of range at offset {}",
+                index,
+                self.pos - 4
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const FLOAT_PROPERTY: u8 = 2;
+    const NAME_PROPERTY: u8 = 11;
+
+    fn push_name_ref(out: &mut Vec<u8>, index: i32) {
+        out.extend(index.to_le_bytes());
+    }
+
+    /// Tables with names, one enum and a `LinearColor` struct holding a float
+    /// and an array of maps, laid out as `version` writes them.
+    fn tables(version: u8) -> Vec<u8> {
+        let names = ["LinearColor", "R", "EMode", "Mode_A", "Tags"];
+        let mut out = Vec::new();
+        out.extend((names.len() as i32).to_le_bytes());
+        for name in names {
+            if version >= UsmapVersion::LongFName as u8 {
+                out.extend((name.len() as u16).to_le_bytes());
+            } else {
+                out.push(name.len() as u8);
+            }
+            out.extend(name.as_bytes());
+        }
+
+        out.extend(1i32.to_le_bytes());
+        push_name_ref(&mut out, 2);
+        if version >= UsmapVersion::LargeEnums as u8 {
+            out.extend(1u16.to_le_bytes());
+        } else {
+            out.push(1);
+        }
+        if version >= UsmapVersion::ExplicitEnumValues as u8 {
+            out.extend(7i64.to_le_bytes());
+        }
+        push_name_ref(&mut out, 3);
+
+        out.extend(1i32.to_le_bytes());
+        push_name_ref(&mut out, 0);
+        push_name_ref(&mut out, -1);
+        out.extend(2u16.to_le_bytes());
+        out.extend(2u16.to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+        out.push(1);
+        push_name_ref(&mut out, 1);
+        out.push(FLOAT_PROPERTY);
+        out.extend(1u16.to_le_bytes());
+        out.push(1);
+        push_name_ref(&mut out, 4);
+        out.extend([
+            ARRAY_PROPERTY,
+            MAP_PROPERTY,
+            NAME_PROPERTY,
+            ENUM_PROPERTY,
+            0,
+        ]);
+        push_name_ref(&mut out, 2);
+        out
+    }
+
+    fn file(version: u8, compression: u8, payload: &[u8], decompressed_size: usize) -> Vec<u8> {
+        let mut out = USMAP_MAGIC.to_le_bytes().to_vec();
+        out.push(version);
+        if version >= UsmapVersion::PackageVersioning as u8 {
+            out.extend(0i32.to_le_bytes());
+        }
+        out.push(compression);
+        out.extend((payload.len() as u32).to_le_bytes());
+        out.extend((decompressed_size as u32).to_le_bytes());
+        out.extend(payload);
+        out
+    }
+
+    fn uncompressed(version: u8) -> Vec<u8> {
+        let tables = tables(version);
+        file(version, 0, &tables, tables.len())
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+        writer.write_all(data).unwrap();
+        drop(writer);
+        out
+    }
+
+    fn format_error(result: Result<Usmap, UsmapError>) -> String {
+        match result {
+            Err(UsmapError::Format(message)) => message,
+            other => panic!("expected a format error, got {:?}", other.map(|u| u.header)),
+        }
+    }
+
+    #[test]
+    fn every_header_version_parses() {
+        for version in 0..=UsmapVersion::ExplicitEnumValues as u8 {
+            let usmap = Usmap::parse(&uncompressed(version)).unwrap();
+            assert_eq!(usmap.header.version as u8, version);
+            assert_eq!(usmap.names.len(), 5);
+            let expected_value = if version >= UsmapVersion::ExplicitEnumValues as u8 {
+                7
+            } else {
+                0
+            };
+            assert_eq!(
+                usmap.enums[0].values,
+                [(expected_value, Some("Mode_A".to_string()))]
+            );
+            assert!(usmap.has_struct("linearcolor"));
+            let properties = &usmap.structs[0].properties;
+            assert!(matches!(
+                properties[0].data,
+                PropertyData::Simple(FLOAT_PROPERTY)
+            ));
+            let PropertyData::Container { inner, .. } = &properties[1].data else {
+                panic!("Tags is not an array: {:?}", properties[1].data);
+            };
+            assert!(matches!(**inner, PropertyData::Map { .. }));
+        }
+    }
+
+    #[test]
+    fn package_versioning_is_read() {
+        let tables = tables(1);
+        let mut data = USMAP_MAGIC.to_le_bytes().to_vec();
+        data.push(1);
+        data.extend(1i32.to_le_bytes());
+        data.extend(522i32.to_le_bytes());
+        data.extend(1009i32.to_le_bytes());
+        data.extend(1i32.to_le_bytes());
+        data.extend([0xAB; 16]);
+        data.extend(3i32.to_le_bytes());
+        data.extend(12345u32.to_le_bytes());
+        data.push(0);
+        data.extend((tables.len() as u32).to_le_bytes());
+        data.extend((tables.len() as u32).to_le_bytes());
+        data.extend(&tables);
+
+        let versioning = Usmap::parse(&data)
+            .unwrap()
+            .header
+            .package_versioning
+            .unwrap();
+        assert_eq!(versioning.file_version_ue4, 522);
+        assert_eq!(versioning.file_version_ue5, 1009);
+        assert_eq!(versioning.custom_version_count, 1);
+        assert_eq!(versioning.net_cl, 12345);
+    }
+
+    #[test]
+    fn compression_kinds() {
+        let tables = tables(4);
+
+        let zstd = zstd::bulk::compress(&tables, 3).unwrap();
+        let usmap = Usmap::parse(&file(4, 3, &zstd, tables.len())).unwrap();
+        assert_eq!(usmap.header.compression, CompressionMethod::ZStandard);
+        assert!(usmap.check_loadable().is_ok());
+
+        let usmap = Usmap::parse(&file(4, 2, &brotli(&tables), tables.len())).unwrap();
+        assert_eq!(usmap.header.compression, CompressionMethod::Brotli);
+        assert_eq!(usmap.structs.len(), 1);
+        // UAssetAPI cannot load Brotli mappings
+        assert!(usmap.check_loadable().is_err());
+
+        assert!(matches!(
+            Usmap::parse(&file(4, 1, &tables, tables.len() * 3)),
+            Err(UsmapError::UnsupportedCompression(CompressionMethod::Oodle))
+        ));
+        assert!(
+            format_error(Usmap::parse(&file(4, 9, &tables, tables.len())))
+                .contains("Compression method 9")
+        );
+        assert!(
+            format_error(Usmap::parse(&file(4, 0, &tables, tables.len() + 1)))
+                .contains("must be equal")
+        );
+    }
+
+    #[test]
+    fn decompressed_size_is_bounded() {
+        let tables = tables(4);
+        let oversized = MAX_DECOMPRESSED_SIZE as usize + 1;
+        for compressed in [zstd::bulk::compress(&tables, 3).unwrap(), brotli(&tables)] {
+            let compression = if compressed[..4] == [0x28, 0xB5, 0x2F, 0xFD] {
+                3
+            } else {
+                2
+            };
+            assert!(
+                format_error(Usmap::parse(&file(4, compression, &compressed, oversized)))
+                    .contains("limit")
+            );
+            // A header that undersells the payload is caught, not trusted
+            assert!(format_error(Usmap::parse(&file(
+                4,
+                compression,
+                &compressed,
+                tables.len() - 1
+            )))
+            .contains(if compression == 2 {
+                "header says"
+            } else {
+                "ZStandard"
+            }));
+        }
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        let data = uncompressed(4);
+        for len in [0, 1, 3, 8, data.len() / 2, data.len() - 1] {
+            assert!(
+                !format_error(Usmap::parse(&data[..len])).is_empty(),
+                "{} bytes",
+                len
+            );
+        }
+
+        // Tables cut short inside a complete header
+        let tables = tables(4);
+        let cut = &tables[..tables.len() - 3];
+        assert!(format_error(Usmap::parse(&file(4, 0, cut, cut.len())))
+            .contains("Unexpected end of data"));
+    }
+
+    #[test]
+    fn bad_magic_and_version_are_rejected() {
+        let mut data = uncompressed(4);
+        data[0] ^= 0xFF;
+        assert_eq!(format_error(Usmap::parse(&data)), "File signature mismatch");
+
+        let mut data = uncompressed(4);
+        data[2] = 42;
+        assert!(format_error(Usmap::parse(&data)).contains("Unknown file version 42"));
+    }
+
+    #[test]
+    fn property_nesting_is_bounded() {
+        let mut tables = Vec::new();
+        tables.extend(1i32.to_le_bytes());
+        tables.extend(1u16.to_le_bytes());
+        tables.push(b'A');
+        tables.extend(0i32.to_le_bytes());
+        tables.extend(1i32.to_le_bytes());
+        push_name_ref(&mut tables, 0);
+        push_name_ref(&mut tables, -1);
+        tables.extend(1u16.to_le_bytes());
+        tables.extend(1u16.to_le_bytes());
+        tables.extend(0u16.to_le_bytes());
+        tables.push(1);
+        push_name_ref(&mut tables, 0);
+        tables.extend([ARRAY_PROPERTY; 64]);
+        tables.push(FLOAT_PROPERTY);
+
+        assert!(
+            format_error(Usmap::parse(&file(4, 0, &tables, tables.len())))
+                .contains("nested deeper")
+        );
+    }
+}
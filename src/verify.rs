@@ -0,0 +1,276 @@
+//! Round-trip verification of written assets.
+//!
+//! The JSON a .uasset was written from is compared against what UAssetCLI
+//! reads back from that .uasset, so properties UAssetAPI silently dropped or
+//! changed show up before a mod ships.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::colors::escape_pointer_token;
+use crate::error::AppError;
+
+/// Fields that legitimately change when an asset is written and read back.
+const VOLATILE_FIELDS: &[&str] = &[
+    // Tool banner, includes the UAssetAPI version
+    "Info",
+    // Layout of the written file, recomputed by UAsset.Write
+    "SerialSize",
+    "SerialOffset",
+    "ScriptSerializationStartOffset",
+    "ScriptSerializationEndOffset",
+    "BulkDataStartOffset",
+    "PayloadTocOffset",
+];
+
+/// Stop listing mismatches after this many; `mismatch_count` keeps counting.
+const MAX_REPORTED_MISMATCHES: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PropertyMismatch {
+    /// JSON pointer into the input JSON (or the re-read JSON for additions).
+    pub pointer: String,
+    /// `None` when the property was added by the round trip.
+    pub expected: Option<Value>,
+    /// `None` when the property was dropped.
+    pub actual: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RoundTripReport {
+    pub mismatch_count: usize,
+    pub mismatches: Vec<PropertyMismatch>,
+    /// Set when the written file could not be read back at all.
+    pub error: Option<AppError>,
+}
+
+impl RoundTripReport {
+    pub fn failed(error: AppError) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.mismatch_count == 0 && self.error.is_none()
+    }
+}
+
+/// Compares the JSON an asset was written from with the JSON read back.
+pub fn compare(expected: &Value, actual: &Value) -> RoundTripReport {
+    let mut report = RoundTripReport::default();
+    diff(expected, actual, String::new(), &mut report);
+    report
+}
+
+fn diff(expected: &Value, actual: &Value, pointer: String, report: &mut RoundTripReport) {
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            for (key, e_value) in e {
+                if VOLATILE_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let child = format!("{}/{}", pointer, escape_pointer_token(key));
+                match a.get(key) {
+                    Some(a_value) => diff(e_value, a_value, child, report),
+                    None => push(report, child, Some(e_value), None),
+                }
+            }
+            for (key, a_value) in a {
+                if !e.contains_key(key) && !VOLATILE_FIELDS.contains(&key.as_str()) {
+                    push(
+                        report,
+                        format!("{}/{}", pointer, escape_pointer_token(key)),
+                        None,
+                        Some(a_value),
+                    );
+                }
+            }
+        }
+        (Value::Array(e), Value::Array(a)) => match (named(e), named(a)) {
+            // Property lists: match by name so one dropped property does not
+            // shift every following index into a mismatch
+            (Some(e_names), Some(a_names)) => {
+                for (i, name) in e_names.iter().enumerate() {
+                    let child = format!("{}/{}", pointer, i);
+                    match a_names.iter().position(|n| n == name) {
+                        Some(j) => diff(&e[i], &a[j], child, report),
+                        None => push(report, child, Some(&e[i]), None),
+                    }
+                }
+                for (j, name) in a_names.iter().enumerate() {
+                    if !e_names.contains(name) {
+                        push(report, format!("{}/{}", pointer, j), None, Some(&a[j]));
+                    }
+                }
+            }
+            _ => {
+                for i in 0..e.len().max(a.len()) {
+                    let child = format!("{}/{}", pointer, i);
+                    match (e.get(i), a.get(i)) {
+                        (Some(e_value), Some(a_value)) => diff(e_value, a_value, child, report),
+                        (e_value, a_value) => push(report, child, e_value, a_value),
+                    }
+                }
+            }
+        },
+        (Value::Number(e), Value::Number(a)) => {
+            if !same_number(e.as_f64(), a.as_f64()) {
+                push(report, pointer, Some(expected), Some(actual));
+            }
+        }
+        _ => {
+            if expected != actual {
+                push(report, pointer, Some(expected), Some(actual));
+            }
+        }
+    }
+}
+
+/// Names of an array of UAssetAPI properties, if every element has a unique
+/// `Name`.
+fn named(items: &[Value]) -> Option<Vec<&str>> {
+    let names: Vec<&str> = items
+        .iter()
+        .map(|item| item.get("Name").and_then(Value::as_str))
+        .collect::<Option<_>>()?;
+    let mut sorted = names.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+    (!names.is_empty() && sorted.len() == names.len()).then_some(names)
+}
+
+/// Most asset values are float32, so edited doubles come back rounded.
+fn same_number(expected: Option<f64>, actual: Option<f64>) -> bool {
+    match (expected, actual) {
+        (Some(e), Some(a)) => e == a || (e as f32) == (a as f32),
+        (e, a) => e == a,
+    }
+}
+
+fn push(
+    report: &mut RoundTripReport,
+    pointer: String,
+    expected: Option<&Value>,
+    actual: Option<&Value>,
+) {
+    report.mismatch_count += 1;
+    if report.mismatches.len() < MAX_REPORTED_MISMATCHES {
+        report.mismatches.push(PropertyMismatch {
+            pointer,
+            expected: expected.map(summarize),
+            actual: actual.map(summarize),
+        });
+    }
+}
+
+/// Keeps reports small: nested values are replaced by their property name.
+fn summarize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => match map.get("Name").and_then(Value::as_str) {
+            Some(name) => Value::String(format!("{{{}}}", name)),
+            None => Value::String("{...}".to_string()),
+        },
+        Value::Array(items) => Value::String(format!("[{} items]", items.len())),
+        scalar => scalar.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn float_property(name: &str, value: Value) -> Value {
+        json!({
+            "$type": "UAssetAPI.PropertyTypes.Objects.FloatPropertyData, UAssetAPI",
+            "Name": name,
+            "Value": value
+        })
+    }
+
+    fn asset(data: Vec<Value>) -> Value {
+        json!({
+            "Info": "Serialized with UAssetAPI 1.0.0",
+            "Exports": [{ "Name": "MI_Test", "SerialSize": 120, "Data": data }]
+        })
+    }
+
+    fn pointers(report: &RoundTripReport) -> Vec<&str> {
+        report
+            .mismatches
+            .iter()
+            .map(|m| m.pointer.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn dropped_property_is_reported() {
+        let expected = asset(vec![
+            float_property("Glow", json!(1.0)),
+            float_property("Tint", json!(0.5)),
+        ]);
+        let actual = asset(vec![float_property("Glow", json!(1.0))]);
+
+        let report = compare(&expected, &actual);
+        assert!(!report.is_clean());
+        assert_eq!(report.mismatch_count, 1);
+        let mismatch = &report.mismatches[0];
+        assert_eq!(mismatch.pointer, "/Exports/0/Data/1");
+        assert_eq!(mismatch.expected, Some(json!("{Tint}")));
+        assert_eq!(mismatch.actual, None);
+    }
+
+    #[test]
+    fn named_properties_match_by_name() {
+        let expected = asset(vec![
+            float_property("A", json!(1.0)),
+            float_property("B", json!(2.0)),
+            float_property("C", json!(3.0)),
+        ]);
+        let reordered = asset(vec![
+            float_property("C", json!(3.0)),
+            float_property("A", json!(1.0)),
+            float_property("B", json!(2.5)),
+        ]);
+
+        // Only the changed value, not every shifted index
+        let report = compare(&expected, &reordered);
+        assert_eq!(pointers(&report), ["/Exports/0/Data/1/Value"]);
+        assert_eq!(report.mismatches[0].expected, Some(json!(2.0)));
+        assert_eq!(report.mismatches[0].actual, Some(json!(2.5)));
+    }
+
+    #[test]
+    fn float32_rounding_is_clean() {
+        let edited = 0.123456789_f64;
+        let expected = asset(vec![float_property("Glow", json!(edited))]);
+        let actual = asset(vec![float_property("Glow", json!(edited as f32 as f64))]);
+        assert!(compare(&expected, &actual).is_clean());
+
+        let actual = asset(vec![float_property("Glow", json!(0.1235))]);
+        assert_eq!(compare(&expected, &actual).mismatch_count, 1);
+    }
+
+    #[test]
+    fn volatile_fields_are_ignored() {
+        let expected = asset(vec![float_property("Glow", json!(1.0))]);
+        let mut actual = expected.clone();
+        actual["Info"] = json!("Serialized with UAssetAPI 1.0.1");
+        actual["Exports"][0]["SerialSize"] = json!(128);
+        actual["Exports"][0]["SerialOffset"] = json!(4096);
+        assert!(compare(&expected, &actual).is_clean());
+    }
+
+    #[test]
+    fn reported_mismatches_are_capped() {
+        let count = MAX_REPORTED_MISMATCHES + 10;
+        let expected = json!((0..count).map(|i| json!(i)).collect::<Vec<_>>());
+        let actual = json!((0..count).map(|i| json!(i + 1)).collect::<Vec<_>>());
+
+        let report = compare(&expected, &actual);
+        assert_eq!(report.mismatch_count, count);
+        assert_eq!(report.mismatches.len(), MAX_REPORTED_MISMATCHES);
+    }
+}
@@ -0,0 +1,71 @@
+//! Hot reload of settings changed on disk by something other than this
+//! editor, e.g. a teammate's `settings.json` or a linked filter dictionary
+//! dropped into place. Files are polled, which also works on network drives.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::ErrorCode;
+use crate::{reload_settings, watched_settings_files, AppState, FileStat};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Settings files as this editor last wrote them. A file that still looks
+/// like that holds nothing new and is not reloaded.
+static OWN_WRITES: LazyLock<Mutex<HashMap<PathBuf, FileStat>>> = LazyLock::new(Default::default);
+
+/// Notes that the editor itself just wrote `path`.
+pub fn record_own_write(path: &Path) {
+    if let Some(stat) = FileStat::of(path) {
+        OWN_WRITES.lock().unwrap().insert(path.to_path_buf(), stat);
+    }
+}
+
+fn is_own_write(path: &Path, stat: Option<FileStat>) -> bool {
+    stat.is_some() && OWN_WRITES.lock().unwrap().get(path).copied() == stat
+}
+
+/// Polls the settings files for the lifetime of the app. Emits
+/// `settings-changed` with the new `LayeredSettings` after a reload, or
+/// `settings-reload-failed` with the error if a changed file is invalid.
+pub fn spawn(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut seen: HashMap<PathBuf, Option<FileStat>> = HashMap::new();
+        // Set while a reload is held off by another one or by a conversion
+        let mut pending = false;
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let state = app.state::<AppState>();
+
+            let mut changed = false;
+            for path in watched_settings_files(&state) {
+                let stat = FileStat::of(&path);
+                // Files seen for the first time were just loaded
+                let previous = seen.insert(path.clone(), stat);
+                if previous.is_some_and(|previous| previous != stat) && !is_own_write(&path, stat) {
+                    changed = true;
+                }
+            }
+            if !changed && !pending {
+                continue;
+            }
+
+            pending = false;
+            match reload_settings(&state) {
+                Ok(Some(layered)) => {
+                    eprintln!("[DEBUG] Settings changed on disk, reloaded");
+                    let _ = app.emit("settings-changed", layered);
+                }
+                Ok(None) => {}
+                Err(e) if e.code == ErrorCode::Busy => pending = true,
+                Err(e) => {
+                    eprintln!("[DEBUG] Failed to reload settings: {}", e);
+                    let _ = app.emit("settings-reload-failed", e);
+                }
+            }
+        }
+    });
+}
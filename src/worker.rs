@@ -0,0 +1,455 @@
+//! Long-lived UAssetCLI process (`UAssetCLI serve`).
+//!
+//! Spawning .NET and parsing the usmap dominates small conversions, so one
+//! worker is kept alive and fed JSON requests over stdin. Each response line
+//! carries what the one-shot command would have printed to stdout. Progress
+//! stays on stderr in the `ConversionProgress` format, followed by a `done`
+//! marker once a request has finished.
+//!
+//! Custom UAssetCLI builds without `serve` still work: they get one process
+//! per request, as before the worker existed.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::error::{AppError, ErrorCode};
+use crate::{tool_command, ConversionProgress, FileStat, ProgressCallback};
+
+/// Longest a request may take before UAssetCLI is assumed to hang. Leaves
+/// room for a full batch of a large mod.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Longest a worker may take to say it is ready.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What a one-shot UAssetCLI run would have produced.
+pub struct WorkerOutput {
+    pub exit_code: i32,
+    pub stdout: String,
+    pub progress: Vec<ConversionProgress>,
+}
+
+#[derive(Serialize)]
+struct WorkerRequest<'a> {
+    id: u64,
+    args: &'a [String],
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkerMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    #[serde(default)]
+    id: u64,
+    #[serde(default)]
+    exit_code: i32,
+    #[serde(default)]
+    output: String,
+}
+
+/// Where stderr progress of the request in flight goes.
+#[derive(Default)]
+struct ProgressRoute {
+    on_progress: Option<ProgressCallback>,
+    seen: Vec<ConversionProgress>,
+}
+
+impl ProgressRoute {
+    fn begin(&mut self, on_progress: ProgressCallback) {
+        self.on_progress = Some(on_progress);
+        self.seen.clear();
+    }
+
+    fn report(&mut self, progress: ConversionProgress) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(&progress);
+        }
+        self.seen.push(progress);
+    }
+
+    /// Stops forwarding and hands out what the request reported.
+    fn finish(&mut self) -> Vec<ConversionProgress> {
+        self.on_progress = None;
+        std::mem::take(&mut self.seen)
+    }
+}
+
+struct WorkerProcess {
+    tool_path: PathBuf,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    route: Arc<Mutex<ProgressRoute>>,
+    /// Id of the last request whose `done` marker was read from stderr.
+    done: watch::Receiver<u64>,
+    stderr_task: JoinHandle<()>,
+    failed: bool,
+}
+
+/// A UAssetCLI build without `serve`, run once per request.
+struct OneShot {
+    tool_path: PathBuf,
+    /// Stat of the build, so a rebuild gets to try worker mode again.
+    stat: Option<FileStat>,
+    route: Arc<Mutex<ProgressRoute>>,
+}
+
+/// Owns the worker process and restarts it when it is gone.
+#[derive(Default)]
+pub struct Worker {
+    process: Option<WorkerProcess>,
+    one_shot: Option<OneShot>,
+    next_id: u64,
+}
+
+impl Worker {
+    /// Makes sure a healthy worker for `tool_path` is running, or that
+    /// requests run one-shot if it has no worker mode.
+    pub async fn start(&mut self, tool_path: &Path) -> Result<(), AppError> {
+        if let Some(one_shot) = &self.one_shot {
+            if one_shot.tool_path == tool_path && one_shot.stat == FileStat::of(tool_path) {
+                return Ok(());
+            }
+            self.one_shot = None;
+        }
+        let reusable = match self.process.as_mut() {
+            Some(p) => {
+                !p.failed && p.tool_path == tool_path && matches!(p.child.try_wait(), Ok(None))
+            }
+            None => false,
+        };
+        if !reusable {
+            if let Some(mut old) = self.process.take() {
+                eprintln!("[DEBUG] Restarting UAssetCLI worker");
+                let _ = old.child.kill().await;
+            }
+            match WorkerProcess::spawn(tool_path).await? {
+                Some(process) => self.process = Some(process),
+                None => {
+                    eprintln!(
+                        "[DEBUG] UAssetCLI at {:?} has no worker mode, running one process per request",
+                        tool_path
+                    );
+                    self.one_shot = Some(OneShot {
+                        tool_path: tool_path.to_path_buf(),
+                        stat: FileStat::of(tool_path),
+                        route: Arc::default(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs one UAssetCLI command (`args` as on the command line) in the
+    /// worker, starting it first if needed.
+    pub async fn request(
+        &mut self,
+        tool_path: &Path,
+        args: &[String],
+        on_progress: ProgressCallback,
+    ) -> Result<WorkerOutput, AppError> {
+        self.start(tool_path).await?;
+        if let Some(one_shot) = &self.one_shot {
+            // Running out of time drops the child, which kills it
+            return tokio::time::timeout(REQUEST_TIMEOUT, one_shot.request(args, on_progress))
+                .await
+                .unwrap_or_else(|_| Err(timed_out()));
+        }
+        let Some(process) = self.process.as_mut() else {
+            return Err(failed("UAssetCLI worker is not running"));
+        };
+
+        self.next_id += 1;
+        let request = process.request(self.next_id, args, on_progress);
+        let result = match tokio::time::timeout(REQUEST_TIMEOUT, request).await {
+            Ok(result) => result,
+            Err(_) => {
+                eprintln!("[DEBUG] UAssetCLI worker stopped answering, killing it");
+                let _ = process.child.kill().await;
+                Err(timed_out())
+            }
+        };
+        // A worker that failed mid-request is not trusted with the next one;
+        // the next request starts a new one. It stays around so `abort` can
+        // still hand out its progress.
+        process.failed = result.is_err();
+        result
+    }
+
+    /// Kills the worker, e.g. to stop a running batch. Returns the progress
+    /// the interrupted or failed request had reported.
+    pub async fn abort(&mut self) -> Vec<ConversionProgress> {
+        if let Some(one_shot) = &self.one_shot {
+            return one_shot.route.lock().unwrap().finish();
+        }
+        let Some(mut process) = self.process.take() else {
+            return Vec::new();
+        };
+        let _ = process.child.kill().await;
+        let _ = process.stderr_task.await;
+        let progress = process.route.lock().unwrap().finish();
+        progress
+    }
+}
+
+/// Workers for one `AppState`. Single requests go to the first one; sharded
+/// batches borrow as many as they have shards.
+#[derive(Default)]
+pub struct WorkerPool {
+    workers: Vec<Worker>,
+}
+
+impl WorkerPool {
+    pub fn primary(&mut self) -> &mut Worker {
+        self.workers(1).first_mut().unwrap()
+    }
+
+    /// The first `count` workers. Processes are only started on their first
+    /// request.
+    pub fn workers(&mut self, count: usize) -> &mut [Worker] {
+        let count = count.max(1);
+        if self.workers.len() < count {
+            self.workers.resize_with(count, Worker::default);
+        }
+        &mut self.workers[..count]
+    }
+
+    /// Stops every worker but the primary one, so idle shards do not keep a
+    /// .NET process and a parsed usmap around.
+    pub fn release_extra(&mut self) {
+        self.workers.truncate(1);
+    }
+}
+
+impl OneShot {
+    async fn request(
+        &self,
+        args: &[String],
+        on_progress: ProgressCallback,
+    ) -> Result<WorkerOutput, AppError> {
+        self.route.lock().unwrap().begin(on_progress);
+        let mut child = tool_command(&self.tool_path)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| not_started(&self.tool_path, e))?;
+
+        let stderr = child.stderr.take();
+        let route = self.route.clone();
+        let stderr_task = tokio::spawn(async move {
+            let Some(stderr) = stderr else {
+                return;
+            };
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match serde_json::from_str::<ConversionProgress>(&line) {
+                    Ok(progress) => route.lock().unwrap().report(progress),
+                    Err(_) => eprintln!("[DEBUG] UAssetCLI stderr: {}", line),
+                }
+            }
+        });
+
+        let mut stdout = String::new();
+        if let Some(mut pipe) = child.stdout.take() {
+            pipe.read_to_string(&mut stdout)
+                .await
+                .map_err(|e| failed(e.to_string()))?;
+        }
+        let status = child.wait().await.map_err(|e| failed(e.to_string()))?;
+        let _ = stderr_task.await;
+        Ok(WorkerOutput {
+            exit_code: status.code().unwrap_or(-1),
+            stdout,
+            progress: self.route.lock().unwrap().finish(),
+        })
+    }
+}
+
+impl WorkerProcess {
+    /// Starts `UAssetCLI serve`. `None` if the build has no worker mode.
+    async fn spawn(tool_path: &Path) -> Result<Option<Self>, AppError> {
+        eprintln!("[DEBUG] Starting UAssetCLI worker: {:?}", tool_path);
+        let mut cmd = tool_command(tool_path);
+        cmd.arg("serve")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = cmd.spawn().map_err(|e| not_started(tool_path, e))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| failed("UAssetCLI worker has no stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| failed("UAssetCLI worker has no stdout"))?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| failed("UAssetCLI worker has no stderr"))?;
+
+        let route = Arc::new(Mutex::new(ProgressRoute::default()));
+        let (done_tx, done) = watch::channel(0);
+        let task_route = route.clone();
+        let stderr_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(message) = serde_json::from_str::<WorkerMessage>(&line) {
+                    if message.message_type == "done" {
+                        let _ = done_tx.send(message.id);
+                        continue;
+                    }
+                }
+                match serde_json::from_str::<ConversionProgress>(&line) {
+                    Ok(progress) => task_route.lock().unwrap().report(progress),
+                    Err(_) => eprintln!("[DEBUG] Worker stderr: {}", line),
+                }
+            }
+        });
+
+        let mut process = Self {
+            tool_path: tool_path.to_path_buf(),
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            route,
+            done,
+            stderr_task,
+            failed: false,
+        };
+
+        match tokio::time::timeout(STARTUP_TIMEOUT, process.handshake()).await {
+            Ok(Ok(true)) => Ok(Some(process)),
+            Ok(Ok(false)) => Ok(None),
+            Ok(Err(e)) => {
+                let _ = process.child.kill().await;
+                Err(e.with_path(tool_path))
+            }
+            Err(_) => {
+                let _ = process.child.kill().await;
+                Err(failed(format!(
+                    "UAssetCLI worker did not start within {} seconds",
+                    STARTUP_TIMEOUT.as_secs()
+                ))
+                .with_path(tool_path))
+            }
+        }
+    }
+
+    /// Waits for the `ready` message. `false` for builds without `serve`,
+    /// which print their usage text and exit instead; a worker that dies
+    /// any other way is an error, and the next request tries again.
+    async fn handshake(&mut self) -> Result<bool, AppError> {
+        let mut printed = String::new();
+        loop {
+            match self.stdout.next_line().await {
+                Ok(Some(line)) => match serde_json::from_str::<WorkerMessage>(&line) {
+                    Ok(message) if message.message_type == "ready" => return Ok(true),
+                    Ok(_) => {}
+                    Err(_) => {
+                        printed.push_str(&line);
+                        printed.push('\n');
+                    }
+                },
+                Ok(None) => break,
+                Err(e) => return Err(failed(e.to_string())),
+            }
+        }
+        let status = self.child.wait().await.map_err(|e| failed(e.to_string()))?;
+        if status.code().is_some() && printed.to_lowercase().contains("usage") {
+            return Ok(false);
+        }
+        Err(failed(format!(
+            "UAssetCLI worker exited while starting ({})",
+            status
+        ))
+        .with_tool_output(printed))
+    }
+
+    async fn request(
+        &mut self,
+        id: u64,
+        args: &[String],
+        on_progress: ProgressCallback,
+    ) -> Result<WorkerOutput, AppError> {
+        self.route.lock().unwrap().begin(on_progress);
+
+        let mut line = serde_json::to_string(&WorkerRequest { id, args })
+            .map_err(|e| failed(e.to_string()))?;
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| failed(format!("UAssetCLI worker stopped: {}", e)))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| failed(format!("UAssetCLI worker stopped: {}", e)))?;
+
+        let response = loop {
+            let message = self.read_message().await?;
+            if message.message_type == "response" && message.id == id {
+                break message;
+            }
+        };
+
+        // Progress printed before the response may still be in the stderr pipe
+        let _ = self.done.wait_for(|done| *done >= id).await;
+
+        Ok(WorkerOutput {
+            exit_code: response.exit_code,
+            stdout: response.output,
+            progress: self.route.lock().unwrap().finish(),
+        })
+    }
+
+    /// Reads the next protocol message from stdout, skipping anything else.
+    async fn read_message(&mut self) -> Result<WorkerMessage, AppError> {
+        loop {
+            match self.stdout.next_line().await {
+                Ok(Some(line)) => {
+                    if let Ok(message) = serde_json::from_str::<WorkerMessage>(&line) {
+                        return Ok(message);
+                    }
+                    eprintln!("[DEBUG] Worker: {}", line);
+                }
+                Ok(None) => return Err(failed("UAssetCLI worker exited unexpectedly")),
+                Err(e) => return Err(failed(e.to_string())),
+            }
+        }
+    }
+}
+
+fn failed(message: impl Into<String>) -> AppError {
+    AppError::new(ErrorCode::ToolFailed, message)
+}
+
+fn timed_out() -> AppError {
+    failed(format!(
+        "UAssetCLI did not answer within {} minutes",
+        REQUEST_TIMEOUT.as_secs() / 60
+    ))
+}
+
+fn not_started(tool_path: &Path, e: std::io::Error) -> AppError {
+    let code = if e.kind() == std::io::ErrorKind::NotFound {
+        ErrorCode::ToolNotFound
+    } else {
+        ErrorCode::ToolFailed
+    };
+    AppError::new(code, format!("UAssetCLI could not be started: {}", e)).with_path(tool_path)
+}